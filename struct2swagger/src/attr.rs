@@ -0,0 +1,576 @@
+//! Reads the `#[serde(...)]` attributes that change what goes on the wire,
+//! and our own `#[swagger(...)]` ones.
+//! The serde combinations the schema would misdescribe, like an untagged
+//! enum with a tag or a flattened field that is also skipped, are reported
+//! here; anything else serde rejects is left to serde to report.
+
+use proc_macro2::{Literal, TokenStream};
+use syn::{Attribute, Lit, Meta, NestedMeta};
+
+use crate::case::RenameRule;
+use crate::ctxt::Ctxt;
+
+fn serde_meta_items(attrs: &[Attribute]) -> Vec<Meta> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("serde"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested.into_iter()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|nested| match nested {
+            NestedMeta::Meta(meta) => Some(meta),
+            NestedMeta::Lit(_) => None,
+        })
+        .collect()
+}
+
+fn swagger_meta_items(cx: &Ctxt, attrs: &[Attribute]) -> Vec<Meta> {
+    let mut items = vec![];
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("swagger")) {
+        match attr.parse_meta() {
+            Ok(Meta::List(list)) => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(meta) => items.push(meta),
+                        NestedMeta::Lit(lit) => {
+                            cx.error_spanned_by(lit, "expected a #[swagger(...)] attribute")
+                        }
+                    }
+                }
+            }
+            Ok(other) => cx.error_spanned_by(other, "expected #[swagger(...)]"),
+            Err(err) => cx.syn_error(err),
+        }
+    }
+
+    items
+}
+
+fn get_lit_bool(cx: &Ctxt, meta: &Meta) -> Option<bool> {
+    match meta {
+        Meta::NameValue(name_value) => match &name_value.lit {
+            Lit::Bool(b) => Some(b.value),
+            lit => {
+                cx.error_spanned_by(lit, "expected `true` or `false`");
+                None
+            }
+        },
+        Meta::Path(_) => Some(true),
+        Meta::List(_) => {
+            cx.error_spanned_by(meta, "expected `true` or `false`");
+            None
+        }
+    }
+}
+
+fn get_lit_str(meta: &Meta) -> Option<String> {
+    match meta {
+        Meta::NameValue(name_value) => match &name_value.lit {
+            Lit::Str(s) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Like `get_lit_str`, but reports anything else than `key = "..."`.
+fn expect_lit_str(cx: &Ctxt, meta: &Meta) -> Option<String> {
+    let value = get_lit_str(meta);
+    if value.is_none() {
+        cx.error_spanned_by(meta, "expected a string literal");
+    }
+    value
+}
+
+fn expect_lit_u64(cx: &Ctxt, meta: &Meta) -> Option<u64> {
+    if let Meta::NameValue(name_value) = meta {
+        if let Lit::Int(int) = &name_value.lit {
+            match int.base10_parse() {
+                Ok(value) => return Some(value),
+                Err(err) => {
+                    cx.syn_error(err);
+                    return None;
+                }
+            }
+        }
+    }
+    cx.error_spanned_by(meta, "expected a non-negative integer");
+    None
+}
+
+/// A number, given as a literal or, since attributes cannot hold negative
+/// literals, as a string: `minimum = 1.5` or `minimum = "-1"`.
+fn expect_lit_number(cx: &Ctxt, meta: &Meta) -> Option<TokenStream> {
+    if let Meta::NameValue(name_value) = meta {
+        match &name_value.lit {
+            Lit::Int(int) => return Some(quote!(#int)),
+            Lit::Float(float) => return Some(quote!(#float)),
+            Lit::Str(s) => {
+                let value = s.value();
+                if let Ok(int) = value.parse::<i64>() {
+                    let int = Literal::i64_unsuffixed(int);
+                    return Some(quote!(#int));
+                }
+                if let Some(float) = value.parse::<f64>().ok().filter(|f| f.is_finite()) {
+                    let float = Literal::f64_unsuffixed(float);
+                    return Some(quote!(#float));
+                }
+            }
+            _ => {}
+        }
+    }
+    cx.error_spanned_by(meta, "expected a number");
+    None
+}
+
+/// Any literal a JSON value can be made of.
+fn expect_lit_value(cx: &Ctxt, meta: &Meta) -> Option<TokenStream> {
+    if let Meta::NameValue(name_value) = meta {
+        let lit = &name_value.lit;
+        if let Lit::Str(_) | Lit::Int(_) | Lit::Float(_) | Lit::Bool(_) = lit {
+            return Some(quote!(#lit));
+        }
+    }
+    cx.error_spanned_by(meta, "expected a string, number or boolean literal");
+    None
+}
+
+/// Reads both `key = "..."` and `key(serialize = "...", deserialize = "...")`.
+fn get_ser_and_de(meta: &Meta) -> (Option<String>, Option<String>) {
+    match meta {
+        Meta::NameValue(_) => {
+            let value = get_lit_str(meta);
+            (value.clone(), value)
+        }
+        Meta::List(list) => {
+            let mut ser = None;
+            let mut de = None;
+            for nested in list.nested.iter() {
+                if let NestedMeta::Meta(meta) = nested {
+                    if meta.path().is_ident("serialize") {
+                        ser = get_lit_str(meta);
+                    } else if meta.path().is_ident("deserialize") {
+                        de = get_lit_str(meta);
+                    }
+                }
+            }
+            (ser, de)
+        }
+        Meta::Path(_) => (None, None),
+    }
+}
+
+/// The text of the `///` comments, without the space following `///`.
+fn get_doc(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(meta) => get_lit_str(&meta),
+            Err(_) => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_owned).unwrap_or(line))
+        .collect();
+
+    let doc = lines.join("\n").trim().to_owned();
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc)
+    }
+}
+
+fn unraw(ident: &syn::Ident) -> String {
+    ident.to_string().trim_start_matches("r#").to_owned()
+}
+
+/// A name which may differ between serialization and deserialization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Name {
+    serialize: String,
+    deserialize: String,
+}
+
+impl Name {
+    fn new(original: String, ser: Option<String>, de: Option<String>) -> Self {
+        Name {
+            serialize: ser.unwrap_or_else(|| original.clone()),
+            deserialize: de.unwrap_or(original),
+        }
+    }
+
+    /// The name serde writes: schemas are described with it.
+    pub fn serialize_name(&self) -> &str {
+        &self.serialize
+    }
+
+    /// The name serde reads: query parameters are named with it.
+    pub fn deserialize_name(&self) -> &str {
+        &self.deserialize
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenameAllRules {
+    serialize: Option<RenameRule>,
+    deserialize: Option<RenameRule>,
+}
+
+impl RenameAllRules {
+    fn from_meta(meta: &Meta) -> Self {
+        let (ser, de) = get_ser_and_de(meta);
+        RenameAllRules {
+            serialize: ser.and_then(|rule| RenameRule::parse(&rule)),
+            deserialize: de.and_then(|rule| RenameRule::parse(&rule)),
+        }
+    }
+
+    fn or(self, other: RenameAllRules) -> Self {
+        RenameAllRules {
+            serialize: self.serialize.or(other.serialize),
+            deserialize: self.deserialize.or(other.deserialize),
+        }
+    }
+}
+
+/// How serde represents an enum on the wire.
+pub enum TagType {
+    /// `{"variant": {...}}`, the default.
+    External,
+    /// `{"tag": "variant", ...}`, from `#[serde(tag = "...")]`.
+    Internal { tag: String },
+    /// `{"tag": "variant", "content": {...}}`, from `#[serde(tag = "...", content = "...")]`.
+    Adjacent { tag: String, content: String },
+    /// The variant content alone, from `#[serde(untagged)]`.
+    None,
+}
+
+pub struct Container {
+    /// The name of the schema, set by `#[serde(rename = "...")]`.
+    pub rename: Option<String>,
+    pub rename_all: RenameAllRules,
+    pub rename_all_fields: RenameAllRules,
+    pub tag: TagType,
+    /// Missing fields are taken from `Default::default()`.
+    pub default: bool,
+    pub doc: Option<String>,
+    /// The path of this crate, set by `#[swagger(crate = "...")]` when it is re-exported.
+    pub krate: Option<syn::Path>,
+}
+
+impl Container {
+    pub fn from_ast(cx: &Ctxt, attrs: &[Attribute]) -> Self {
+        let mut rename = None;
+        let mut rename_all = RenameAllRules::default();
+        let mut rename_all_fields = RenameAllRules::default();
+        let mut tag = None;
+        let mut content = None;
+        let mut untagged = None;
+        let mut default = false;
+
+        for meta in serde_meta_items(attrs) {
+            if meta.path().is_ident("rename") {
+                rename = get_ser_and_de(&meta).0;
+            } else if meta.path().is_ident("rename_all") {
+                rename_all = RenameAllRules::from_meta(&meta);
+            } else if meta.path().is_ident("rename_all_fields") {
+                rename_all_fields = RenameAllRules::from_meta(&meta);
+            } else if meta.path().is_ident("tag") {
+                tag = get_lit_str(&meta).map(|value| (meta.clone(), value));
+            } else if meta.path().is_ident("content") {
+                content = get_lit_str(&meta).map(|value| (meta.clone(), value));
+            } else if meta.path().is_ident("untagged") {
+                untagged = Some(meta);
+            } else if meta.path().is_ident("default") {
+                default = true;
+            }
+        }
+
+        let mut krate = None;
+
+        for meta in swagger_meta_items(cx, attrs) {
+            if meta.path().is_ident("crate") {
+                if let Some(path) = expect_lit_str(cx, &meta) {
+                    match syn::parse_str(&path) {
+                        Ok(path) => krate = Some(path),
+                        Err(_) => {
+                            cx.error_spanned_by(&meta, format!("invalid crate path {:?}", path))
+                        }
+                    }
+                }
+            } else {
+                cx.error_spanned_by(meta.path(), "unknown swagger container attribute");
+            }
+        }
+
+        let tag = match (untagged, tag, content) {
+            (Some(untagged), tag, content) => {
+                if tag.is_some() || content.is_some() {
+                    cx.error_spanned_by(
+                        untagged,
+                        "an enum cannot be both #[serde(untagged)] and tagged, remove either one",
+                    );
+                }
+                TagType::None
+            }
+            (None, Some((_, tag)), Some((_, content))) => TagType::Adjacent { tag, content },
+            (None, Some((_, tag)), None) => TagType::Internal { tag },
+            (None, None, Some((content, _))) => {
+                cx.error_spanned_by(
+                    content,
+                    "#[serde(content = \"...\")] needs #[serde(tag = \"...\")] as well",
+                );
+                TagType::External
+            }
+            (None, None, None) => TagType::External,
+        };
+
+        Container {
+            rename,
+            rename_all,
+            rename_all_fields,
+            tag,
+            default,
+            doc: get_doc(attrs),
+            krate,
+        }
+    }
+}
+
+pub struct Variant {
+    pub name: Name,
+    /// The rules applied to the fields of a struct variant.
+    pub rename_all: RenameAllRules,
+    /// The variant is never serialized.
+    pub skip_serializing: bool,
+    pub doc: Option<String>,
+}
+
+impl Variant {
+    pub fn from_ast(cx: &Ctxt, variant: &syn::Variant, container: &Container) -> Self {
+        let mut rename = (None, None);
+        let mut rename_all = RenameAllRules::default();
+        let mut skip_serializing = false;
+
+        for meta in serde_meta_items(&variant.attrs) {
+            if meta.path().is_ident("rename") {
+                rename = get_ser_and_de(&meta);
+            } else if meta.path().is_ident("rename_all") {
+                rename_all = RenameAllRules::from_meta(&meta);
+            } else if meta.path().is_ident("skip") || meta.path().is_ident("skip_serializing") {
+                skip_serializing = true;
+            }
+        }
+
+        for meta in swagger_meta_items(cx, &variant.attrs) {
+            cx.error_spanned_by(meta.path(), "unknown swagger variant attribute");
+        }
+
+        let original = unraw(&variant.ident);
+        let rules = container.rename_all;
+        let name = Name::new(
+            original.clone(),
+            rename
+                .0
+                .or_else(|| rules.serialize.map(|rule| rule.apply_to_variant(&original))),
+            rename.1.or_else(|| {
+                rules
+                    .deserialize
+                    .map(|rule| rule.apply_to_variant(&original))
+            }),
+        );
+
+        Variant {
+            name,
+            rename_all: rename_all.or(container.rename_all_fields),
+            skip_serializing,
+            doc: get_doc(&variant.attrs),
+        }
+    }
+}
+
+/// The `#[swagger(...)]` keywords that override or extend the schema of a type.
+#[derive(Default)]
+pub struct Schema {
+    pub description: Option<String>,
+    pub example: Option<TokenStream>,
+    pub format: Option<String>,
+    pub minimum: Option<TokenStream>,
+    pub maximum: Option<TokenStream>,
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+    pub pattern: Option<String>,
+    pub min_items: Option<u64>,
+    pub max_items: Option<u64>,
+    pub unique_items: Option<bool>,
+    pub deprecated: Option<bool>,
+    pub read_only: Option<bool>,
+    pub write_only: Option<bool>,
+    pub nullable: Option<bool>,
+}
+
+impl Schema {
+    /// Reads `meta` if it is one of the schema keywords, and tells whether it was.
+    fn parse_meta(&mut self, cx: &Ctxt, meta: &Meta) -> bool {
+        let path = meta.path();
+        if path.is_ident("description") {
+            self.description = expect_lit_str(cx, meta);
+        } else if path.is_ident("example") {
+            self.example = expect_lit_value(cx, meta);
+        } else if path.is_ident("format") {
+            self.format = expect_lit_str(cx, meta);
+        } else if path.is_ident("minimum") {
+            self.minimum = expect_lit_number(cx, meta);
+        } else if path.is_ident("maximum") {
+            self.maximum = expect_lit_number(cx, meta);
+        } else if path.is_ident("min_length") {
+            self.min_length = expect_lit_u64(cx, meta);
+        } else if path.is_ident("max_length") {
+            self.max_length = expect_lit_u64(cx, meta);
+        } else if path.is_ident("pattern") {
+            self.pattern = expect_lit_str(cx, meta);
+        } else if path.is_ident("min_items") {
+            self.min_items = expect_lit_u64(cx, meta);
+        } else if path.is_ident("max_items") {
+            self.max_items = expect_lit_u64(cx, meta);
+        } else if path.is_ident("unique_items") {
+            self.unique_items = get_lit_bool(cx, meta);
+        } else if path.is_ident("deprecated") {
+            self.deprecated = get_lit_bool(cx, meta);
+        } else if path.is_ident("read_only") {
+            self.read_only = get_lit_bool(cx, meta);
+        } else if path.is_ident("write_only") {
+            self.write_only = get_lit_bool(cx, meta);
+        } else if path.is_ident("nullable") {
+            self.nullable = get_lit_bool(cx, meta);
+        } else {
+            return false;
+        }
+        true
+    }
+}
+
+pub struct Field {
+    pub name: Name,
+    pub flatten: bool,
+    pub skip_serializing: bool,
+    pub skip_deserializing: bool,
+    /// Missing values are filled in by `#[serde(default)]`, on the field or on the container.
+    pub default: bool,
+    pub skip_serializing_if: bool,
+    /// Set by `#[swagger(required = ...)]`, it wins over what the type says.
+    pub required: Option<bool>,
+    /// Set by `#[swagger(as_string = ...)]`, it wins over `integers_as_strings`.
+    pub as_string: Option<bool>,
+    /// Set by `#[swagger(any)]`: the field holds any JSON value, whatever its type.
+    pub any: bool,
+    pub doc: Option<String>,
+    pub schema: Schema,
+}
+
+impl Field {
+    /// `rules` are the `rename_all` rules of the struct or struct variant owning the field.
+    pub fn from_ast(
+        cx: &Ctxt,
+        field: &syn::Field,
+        rules: RenameAllRules,
+        container_default: bool,
+    ) -> Self {
+        let mut rename = (None, None);
+        let mut flatten = None;
+        let mut skip = None;
+        let mut skip_serializing = false;
+        let mut skip_deserializing = false;
+        let mut default = container_default;
+        let mut skip_serializing_if = false;
+
+        for meta in serde_meta_items(&field.attrs) {
+            let path = meta.path();
+            if path.is_ident("rename") {
+                rename = get_ser_and_de(&meta);
+            } else if path.is_ident("flatten") {
+                flatten = Some(meta.clone());
+            } else if path.is_ident("skip") {
+                skip_serializing = true;
+                skip_deserializing = true;
+                skip = Some(meta.clone());
+            } else if path.is_ident("skip_serializing") {
+                skip_serializing = true;
+                skip = Some(meta.clone());
+            } else if path.is_ident("skip_deserializing") {
+                skip_deserializing = true;
+                skip = Some(meta.clone());
+            } else if path.is_ident("default") {
+                default = true;
+            } else if path.is_ident("skip_serializing_if") {
+                skip_serializing_if = true;
+            }
+        }
+
+        let original = unraw(field.ident.as_ref().unwrap());
+        let name = Name::new(
+            original.clone(),
+            rename
+                .0
+                .or_else(|| rules.serialize.map(|rule| rule.apply_to_field(&original))),
+            rename
+                .1
+                .or_else(|| rules.deserialize.map(|rule| rule.apply_to_field(&original))),
+        );
+
+        let mut required = None;
+        let mut as_string = None;
+        let mut any = None;
+        let mut schema = Schema::default();
+
+        for meta in swagger_meta_items(cx, &field.attrs) {
+            if meta.path().is_ident("required") {
+                required = get_lit_bool(cx, &meta);
+            } else if meta.path().is_ident("as_string") {
+                as_string = get_lit_bool(cx, &meta);
+            } else if meta.path().is_ident("any") {
+                any = get_lit_bool(cx, &meta).map(|value| (meta.clone(), value));
+            } else if !schema.parse_meta(cx, &meta) {
+                cx.error_spanned_by(meta.path(), "unknown swagger field attribute");
+            }
+        }
+
+        if let (Some(flatten), Some(skip)) = (&flatten, &skip) {
+            cx.error_spanned_by(
+                flatten,
+                format!(
+                    "#[serde(flatten)] cannot be combined with #[serde({})], skip the fields of the flattened type instead",
+                    skip.path().get_ident().unwrap()
+                ),
+            );
+        }
+
+        if let (true, Some((any, true))) = (flatten.is_some(), &any) {
+            cx.error_spanned_by(
+                any,
+                "#[swagger(any)] cannot be combined with #[serde(flatten)]",
+            );
+        }
+
+        if schema.read_only == Some(true) && schema.write_only == Some(true) {
+            cx.error_spanned_by(field, "a field cannot be both read_only and write_only");
+        }
+
+        Field {
+            name,
+            flatten: flatten.is_some(),
+            skip_serializing,
+            skip_deserializing,
+            default,
+            skip_serializing_if,
+            required,
+            as_string,
+            any: any.is_some_and(|(_, value)| value),
+            doc: get_doc(&field.attrs),
+            schema,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use quote::ToTokens;
+use std::cell::RefCell;
+use std::fmt::Display;
+
+/// Collects the errors found while reading the input, so that all of them are
+/// reported at once instead of stopping at the first one.
+#[derive(Default)]
+pub struct Ctxt {
+    errors: RefCell<Vec<syn::Error>>,
+}
+
+impl Ctxt {
+    pub fn new() -> Self {
+        Ctxt::default()
+    }
+
+    /// Adds an error pointing at `obj`.
+    pub fn error_spanned_by<A: ToTokens, T: Display>(&self, obj: A, msg: T) {
+        self.errors
+            .borrow_mut()
+            .push(syn::Error::new_spanned(obj.into_token_stream(), msg));
+    }
+
+    pub fn syn_error(&self, err: syn::Error) {
+        self.errors.borrow_mut().push(err);
+    }
+
+    /// The `compile_error!` invocations for every error collected, if any.
+    pub fn check(self) -> Result<(), proc_macro2::TokenStream> {
+        let errors = self.errors.into_inner();
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(errors.iter().map(syn::Error::to_compile_error).collect())
+    }
+}
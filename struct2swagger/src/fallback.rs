@@ -0,0 +1,75 @@
+//! `JsonSchemaDefinition` and `QueryDefinition` each have two methods which
+//! default to one another, so that an implementation only writes either.
+//! One that writes neither would call them back and forth until the stack
+//! overflows: the defaults go through `guard`, which panics instead.
+
+use std::cell::RefCell;
+
+thread_local! {
+    /// The `(methods, type)` pairs whose default method is running.
+    static RUNNING: RefCell<Vec<(&'static str, &'static str)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Removes its pair from `RUNNING` once the default method returns, or unwinds.
+struct Running;
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        RUNNING.with(|running| running.borrow_mut().pop());
+    }
+}
+
+/// Runs `f`, the body of a default method of `T`. `methods` names the two
+/// methods defaulting to one another, one of which `T` has to implement.
+pub(crate) fn guard<T: ?Sized, R>(methods: &'static str, f: impl FnOnce() -> R) -> R {
+    let ty = std::any::type_name::<T>();
+    RUNNING.with(|running| {
+        let mut running = running.borrow_mut();
+        if running.contains(&(methods, ty)) {
+            panic!("{} has to implement {}", ty, methods);
+        }
+        running.push((methods, ty));
+    });
+    let _running = Running;
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{JsonSchemaDefinition, QueryDefinition, SchemaGenerator};
+
+    struct Neither;
+
+    impl JsonSchemaDefinition for Neither {}
+    impl QueryDefinition for Neither {}
+
+    struct Legacy;
+
+    impl JsonSchemaDefinition for Legacy {
+        fn get_json_schema_definition() -> serde_json::Value {
+            json!({ "type": "string" })
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "implement json_schema or get_json_schema_definition")]
+    fn json_schema_without_implementation() {
+        Neither::json_schema(&mut SchemaGenerator::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "implement query_definitions or get_query_definitions")]
+    fn query_definitions_without_implementation() {
+        Neither::get_query_definitions();
+    }
+
+    #[test]
+    fn either_method_is_enough() {
+        let expected = json!({ "type": "string" });
+        assert_eq!(
+            Legacy::json_schema(&mut SchemaGenerator::default()),
+            expected
+        );
+        assert_eq!(Legacy::get_json_schema_definition(), expected);
+    }
+}
@@ -0,0 +1,501 @@
+//! The state shared while generating the schemas of a document: its settings,
+//! the component schemas collected so far and the types being generated.
+//!
+//! Named types are what makes recursive types possible: a type met again
+//! while its own schema is being generated is replaced by a `$ref` to it.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde_json::Value;
+
+use crate::schema::extend_schema;
+use crate::swagger_object::{DiscriminatorObject, SwaggerVersion};
+use crate::JsonSchemaDefinition;
+
+#[derive(Debug, Clone)]
+pub struct SchemaSettings {
+    /// The OpenAPI version the schemas are written for, which changes how `null` is described.
+    pub version: SwaggerVersion,
+    /// Describe the named types once in `components/schemas` and reference
+    /// them with `$ref`, instead of inlining them wherever they are used.
+    pub component_schemas: bool,
+    /// `Option<T>` also accepts `null`, instead of only making its property optional.
+    pub option_nullable: bool,
+    /// Give every number its full `minimum`/`maximum`, instead of only the
+    /// bounds its `format` does not imply.
+    pub numeric_bounds: bool,
+    /// Describe 64 and 128-bit integers as strings of digits, the way they
+    /// are written when serialized with serde_with's `DisplayFromStr`.
+    pub integers_as_strings: bool,
+}
+
+impl Default for SchemaSettings {
+    fn default() -> Self {
+        SchemaSettings {
+            version: SwaggerVersion::V300,
+            component_schemas: false,
+            option_nullable: false,
+            numeric_bounds: false,
+            integers_as_strings: false,
+        }
+    }
+}
+
+/// The name of a type without a `schema_name`, used for its `$ref` when it is
+/// recursive and in the name of the derived generic types it is given to:
+/// its type name without the module paths, `Vec_u8` for `Vec<u8>`.
+#[doc(hidden)]
+pub fn fallback_name<T: ?Sized>() -> String {
+    let mut segments = vec![];
+    let mut segment = String::new();
+    let mut chars = std::any::type_name::<T>().chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else if c == ':' && chars.peek() == Some(&':') {
+            // What came before was a module
+            chars.next();
+            segment.clear();
+        } else if !segment.is_empty() {
+            segments.push(std::mem::take(&mut segment));
+        }
+    }
+    if !segment.is_empty() {
+        segments.push(segment);
+    }
+    segments.join("_")
+}
+
+/// A type, by `type_name`, along with the `integers_as_strings` override its
+/// schema was generated with: each override changes the schema, so it makes
+/// a type of its own.
+type TypeKey = (&'static str, Option<bool>);
+
+#[derive(Debug, Clone, Default)]
+pub struct SchemaGenerator {
+    settings: SchemaSettings,
+    definitions: BTreeMap<String, Value>,
+    /// The name each type referenced with `$ref` was given.
+    names: BTreeMap<TypeKey, String>,
+    /// The types referenced from their own schema.
+    recursive: BTreeSet<String>,
+    /// The types whose schema is being generated.
+    in_progress: BTreeSet<TypeKey>,
+    /// Set by `#[swagger(as_string)]` while the schema of its field is generated.
+    integers_as_strings: Option<bool>,
+}
+
+impl SchemaGenerator {
+    pub fn new(settings: SchemaSettings) -> Self {
+        SchemaGenerator {
+            settings,
+            ..SchemaGenerator::default()
+        }
+    }
+
+    pub fn settings(&self) -> &SchemaSettings {
+        &self.settings
+    }
+
+    /// The component schemas registered so far, by name.
+    pub fn definitions(&self) -> &BTreeMap<String, Value> {
+        &self.definitions
+    }
+
+    pub fn take_definitions(&mut self) -> BTreeMap<String, Value> {
+        self.names.clear();
+        std::mem::take(&mut self.definitions)
+    }
+
+    /// The `$ref` pointing at the component schema registered as `name`.
+    pub fn reference(name: &str) -> Value {
+        json!({ "$ref": format!("#/components/schemas/{}", name) })
+    }
+
+    /// The schema of `T` as seen from the schema holding it.
+    ///
+    /// With `component_schemas`, a type with a `schema_name` is registered
+    /// under that name the first time it is met, and a `$ref` to it is
+    /// returned. Otherwise the schema of `T` is inlined, unless `T` is recursive.
+    pub fn subschema_for<T: JsonSchemaDefinition + ?Sized>(&mut self) -> Value {
+        let ty = self.type_key::<T>();
+        let name = T::schema_name();
+
+        if self.in_progress.contains(&ty) {
+            // A recursive type without a name still needs one for its `$ref`
+            let name = self.component_name::<T>(name.unwrap_or_else(fallback_name::<T>));
+            self.recursive.insert(name.clone());
+            return Self::reference(&name);
+        }
+        if let Some(name) = self.names.get(&ty) {
+            if self.definitions.contains_key(name) {
+                return Self::reference(name);
+            }
+        }
+
+        // Unnamed types are only marked by a derived `json_schema` calling
+        // `define`: `Vec<T>` is not recursive, `T` is.
+        let schema = match name {
+            Some(_) => self.define::<T>(T::json_schema),
+            None => T::json_schema(self),
+        };
+        let name = match (name, self.names.get(&ty)) {
+            (Some(name), _) if self.settings.component_schemas => self.component_name::<T>(name),
+            (_, Some(name)) if self.recursive.contains(name) => name.clone(),
+            _ => return schema,
+        };
+        self.definitions.insert(name.clone(), schema);
+        Self::reference(&name)
+    }
+
+    /// The name `T` is registered under, `name` unless another type has it
+    /// already: `a::User` and `b::User` become `User` and `User_2`. Under
+    /// `#[swagger(as_string)]`, `User` is `User_as_string`.
+    fn component_name<T: ?Sized>(&mut self, name: String) -> String {
+        let ty = self.type_key::<T>();
+        if let Some(name) = self.names.get(&ty) {
+            return name.clone();
+        }
+
+        let name = match self.integers_as_strings {
+            Some(true) => format!("{}_as_string", name),
+            Some(false) => format!("{}_as_number", name),
+            None => name,
+        };
+
+        let mut unique = name.clone();
+        let mut suffix = 1;
+        while self.names.values().any(|taken| *taken == unique)
+            || self.definitions.contains_key(&unique)
+        {
+            suffix += 1;
+            unique = format!("{}_{}", name, suffix);
+        }
+        self.names.insert(ty, unique.clone());
+        unique
+    }
+
+    /// The schema of `T` standing on its own, as returned by
+    /// `get_json_schema_definition`. The component schemas its `$ref`s point
+    /// at, those of the recursive types, are left in `definitions`.
+    pub fn root_schema_for<T: JsonSchemaDefinition + ?Sized>(&mut self) -> Value {
+        let schema = self.subschema_for::<T>();
+        schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix("#/components/schemas/"))
+            .and_then(|name| self.definitions.get(name))
+            .cloned()
+            .unwrap_or(schema)
+    }
+
+    /// Runs `f`, the body of `T::json_schema`, with `T` marked as in progress:
+    /// `T` met again inside `f` is a `$ref` instead of an endless recursion.
+    pub fn define<T: JsonSchemaDefinition + ?Sized>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Value,
+    ) -> Value {
+        let ty = self.type_key::<T>();
+        let marked = self.in_progress.insert(ty);
+        let schema = f(self);
+        if marked {
+            self.in_progress.remove(&ty);
+        }
+        schema
+    }
+
+    fn type_key<T: ?Sized>(&self) -> TypeKey {
+        (std::any::type_name::<T>(), self.integers_as_strings)
+    }
+
+    /// Runs `f` with every integer described as a string of digits, or none
+    /// of them, whatever `integers_as_strings` says.
+    pub fn with_integers_as_strings<R>(
+        &mut self,
+        as_strings: bool,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let previous = self.integers_as_strings.replace(as_strings);
+        let result = f(self);
+        self.integers_as_strings = previous;
+        result
+    }
+
+    /// Whether an integer is described as a string of digits. `wide` tells
+    /// whether it has 64 bits or more.
+    pub fn integer_as_string(&self, wide: bool) -> bool {
+        self.integers_as_strings
+            .unwrap_or(self.settings.integers_as_strings && wide)
+    }
+
+    /// The schema of an array holding exactly `items`, one per position, the
+    /// way serde writes a tuple. OpenAPI 3.0 has no keyword for that: there,
+    /// every position accepts any of `items`, and only the length is exact.
+    pub fn tuple_schema(&self, items: Vec<Value>) -> Value {
+        let len = items.len();
+        match self.settings.version {
+            SwaggerVersion::V300 => {
+                let mut any_of: Vec<Value> = Vec::with_capacity(len);
+                for item in items {
+                    if !any_of.contains(&item) {
+                        any_of.push(item);
+                    }
+                }
+                let items = if any_of.len() == 1 {
+                    any_of.remove(0)
+                } else {
+                    json!({ "anyOf": any_of })
+                };
+                json!({
+                    "type": "array",
+                    "items": items,
+                    "minItems": len,
+                    "maxItems": len,
+                })
+            }
+            SwaggerVersion::V310 => json!({
+                "type": "array",
+                "prefixItems": items,
+                "minItems": len,
+                "maxItems": len,
+            }),
+        }
+    }
+
+    /// The schema of an enum `T` whose `tag` property names the variant,
+    /// `variants` being the name and the schema of each of them.
+    ///
+    /// The discriminator only maps the names to the variants when these are
+    /// components of their own: with `component_schemas`, each variant is
+    /// registered as `<T>_<variant>`, `Shape_Circle`, and referenced.
+    pub fn internally_tagged_schema<T: JsonSchemaDefinition + ?Sized>(
+        &mut self,
+        tag: &str,
+        variants: Vec<(&str, Value)>,
+    ) -> Value {
+        let mut discriminator = DiscriminatorObject {
+            property_name: tag.to_owned(),
+            mapping: None,
+        };
+        if !self.settings.component_schemas {
+            let schemas: Vec<Value> = variants.into_iter().map(|(_, schema)| schema).collect();
+            return json!({ "oneOf": schemas, "discriminator": discriminator });
+        }
+
+        let enum_name = T::schema_name().unwrap_or_else(fallback_name::<T>);
+        let mut mapping = HashMap::new();
+        let mut schemas = vec![];
+        for (variant, schema) in variants {
+            let name = self.variant_name(format!("{}_{}", enum_name, variant), schema);
+            let reference = format!("#/components/schemas/{}", name);
+            schemas.push(json!({ "$ref": reference }));
+            mapping.insert(variant.to_owned(), reference);
+        }
+        discriminator.mapping = Some(mapping);
+        json!({ "oneOf": schemas, "discriminator": discriminator })
+    }
+
+    /// Registers the schema of a variant as `name`, unless a type or another
+    /// schema has that name already. The same schema registered again, by a
+    /// flattened enum for instance, keeps its name.
+    fn variant_name(&mut self, name: String, schema: Value) -> String {
+        let mut unique = name.clone();
+        let mut suffix = 1;
+        loop {
+            match self.definitions.get(&unique) {
+                Some(registered) if *registered == schema => return unique,
+                None if !self.names.values().any(|taken| *taken == unique) => {
+                    self.definitions.insert(unique.clone(), schema);
+                    return unique;
+                }
+                _ => {
+                    suffix += 1;
+                    unique = format!("{}_{}", name, suffix);
+                }
+            }
+        }
+    }
+
+    /// The schema of `null`.
+    pub fn null_schema(&self) -> Value {
+        match self.settings.version {
+            // OpenAPI 3.0 has no null type, only nullable schemas
+            SwaggerVersion::V300 => json!({ "nullable": true, "enum": [null] }),
+            SwaggerVersion::V310 => json!({ "type": "null" }),
+        }
+    }
+
+    /// `schema`, also accepting `null`.
+    pub fn nullable_schema(&self, schema: Value) -> Value {
+        match self.settings.version {
+            SwaggerVersion::V300 => extend_schema(schema, json!({ "nullable": true })),
+            SwaggerVersion::V310 => {
+                let single_type = schema
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .map(str::to_owned);
+                match single_type {
+                    Some(ty) if schema.get("enum").is_none() && schema.get("const").is_none() => {
+                        extend_schema(schema, json!({ "type": [ty, "null"] }))
+                    }
+                    _ => json!({ "anyOf": [schema, { "type": "null" }] }),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct User;
+
+    impl JsonSchemaDefinition for User {
+        fn json_schema(generator: &mut SchemaGenerator) -> Value {
+            json!({ "type": "object", "properties": { "age": generator.subschema_for::<u8>() } })
+        }
+
+        fn schema_name() -> Option<String> {
+            Some("User".to_owned())
+        }
+    }
+
+    struct Node;
+
+    impl JsonSchemaDefinition for Node {
+        fn json_schema(generator: &mut SchemaGenerator) -> Value {
+            generator.define::<Node>(|generator| {
+                json!({
+                    "type": "object",
+                    "properties": { "children": generator.subschema_for::<Vec<Node>>() },
+                })
+            })
+        }
+
+        fn schema_name() -> Option<String> {
+            Some("Node".to_owned())
+        }
+    }
+
+    fn components() -> SchemaGenerator {
+        SchemaGenerator::new(SchemaSettings {
+            component_schemas: true,
+            ..SchemaSettings::default()
+        })
+    }
+
+    #[test]
+    fn subschema_for_inlines_by_default() {
+        let mut generator = SchemaGenerator::default();
+
+        assert_eq!(
+            generator.subschema_for::<User>(),
+            User::get_json_schema_definition()
+        );
+        assert!(generator.definitions().is_empty());
+    }
+
+    #[test]
+    fn component_schemas_registers_named_types() {
+        let mut generator = components();
+
+        assert_eq!(
+            generator.subschema_for::<Vec<User>>(),
+            json!({ "type": "array", "items": { "$ref": "#/components/schemas/User" } })
+        );
+        assert_eq!(generator.definitions().len(), 1);
+        assert_eq!(
+            generator.definitions()["User"],
+            User::get_json_schema_definition()
+        );
+    }
+
+    mod other {
+        use super::*;
+
+        pub struct User;
+
+        impl JsonSchemaDefinition for User {
+            fn json_schema(_generator: &mut SchemaGenerator) -> Value {
+                json!({ "type": "string" })
+            }
+
+            fn schema_name() -> Option<String> {
+                Some("User".to_owned())
+            }
+        }
+    }
+
+    #[test]
+    fn component_names_are_unique() {
+        let mut generator = components();
+
+        assert_eq!(
+            generator.subschema_for::<User>(),
+            json!({ "$ref": "#/components/schemas/User" })
+        );
+        assert_eq!(
+            generator.subschema_for::<other::User>(),
+            json!({ "$ref": "#/components/schemas/User_2" })
+        );
+        assert_eq!(
+            generator.subschema_for::<User>(),
+            json!({ "$ref": "#/components/schemas/User" })
+        );
+        assert_eq!(
+            generator.take_definitions().into_iter().collect::<Vec<_>>(),
+            vec![
+                ("User".to_owned(), User::get_json_schema_definition()),
+                ("User_2".to_owned(), json!({ "type": "string" })),
+            ]
+        );
+    }
+
+    #[test]
+    fn recursive_types_are_referenced() {
+        let node = json!({
+            "type": "object",
+            "properties": {
+                "children": { "type": "array", "items": { "$ref": "#/components/schemas/Node" } },
+            },
+        });
+        assert_eq!(Node::get_json_schema_definition(), node);
+
+        let mut generator = SchemaGenerator::default();
+        assert_eq!(generator.root_schema_for::<Node>(), node);
+        assert_eq!(generator.take_definitions()["Node"], node);
+        assert_eq!(
+            generator.subschema_for::<Vec<Node>>(),
+            json!({ "type": "array", "items": { "$ref": "#/components/schemas/Node" } })
+        );
+        assert_eq!(
+            generator.take_definitions().into_iter().collect::<Vec<_>>(),
+            vec![("Node".to_owned(), node)]
+        );
+    }
+
+    #[test]
+    fn nullable_schema_per_version() {
+        let v300 = SchemaGenerator::default();
+        let v310 = SchemaGenerator::new(SchemaSettings {
+            version: SwaggerVersion::V310,
+            ..SchemaSettings::default()
+        });
+        let string = json!({ "type": "string" });
+        let status = json!({ "type": "string", "enum": ["on", "off"] });
+
+        assert_eq!(
+            v300.nullable_schema(string.clone()),
+            json!({ "type": "string", "nullable": true })
+        );
+        assert_eq!(
+            v310.nullable_schema(string),
+            json!({ "type": ["string", "null"] })
+        );
+        assert_eq!(
+            v310.nullable_schema(status.clone()),
+            json!({ "anyOf": [status, { "type": "null" }] })
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! The schema of `bigdecimal::BigDecimal`, written as a string by bigdecimal's
+//! `serde` feature, with an exponent when it is far from 1.
+
+use bigdecimal::BigDecimal;
+
+use crate::{JsonSchemaDefinition, SchemaGenerator};
+
+const BIG_DECIMAL_PATTERN: &str = r"^-?[0-9]+(\.[0-9]+)?([eE][+-]?[0-9]+)?$";
+
+impl JsonSchemaDefinition for BigDecimal {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "pattern": BIG_DECIMAL_PATTERN,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::assert_matches_pattern;
+    use std::str::FromStr;
+
+    #[test]
+    fn big_decimal() {
+        let schema = BigDecimal::get_json_schema_definition();
+
+        for decimal in &["1.50", "-0.000000000000000000001", "1e100", "123456789e-3"] {
+            let value = serde_json::to_value(BigDecimal::from_str(decimal).unwrap()).unwrap();
+            assert_matches_pattern(&schema, &value);
+        }
+        assert!(!regex::Regex::new(BIG_DECIMAL_PATTERN)
+            .unwrap()
+            .is_match("1.5.0"));
+    }
+}
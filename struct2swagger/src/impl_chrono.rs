@@ -0,0 +1,113 @@
+//! The schemas of the `chrono` types, as serialized by chrono's `serde` feature.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone};
+
+use crate::{JsonSchemaDefinition, SchemaGenerator};
+
+// RFC 3339, with the offset of the time zone
+impl<Tz: TimeZone> JsonSchemaDefinition for DateTime<Tz> {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "format": "date-time",
+        })
+    }
+}
+
+impl JsonSchemaDefinition for NaiveDate {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "format": "date",
+        })
+    }
+}
+
+// No offset, which the `time` format of RFC 3339 requires
+impl JsonSchemaDefinition for NaiveTime {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "format": "time",
+            "pattern": r"^\d{2}:\d{2}:\d{2}(\.\d+)?$",
+        })
+    }
+}
+
+impl JsonSchemaDefinition for NaiveDateTime {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "pattern": r"^[+-]?\d{4,}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?$",
+        })
+    }
+}
+
+// `Duration` is an alias of `TimeDelta`, serialized as `[seconds, nanoseconds]`
+impl JsonSchemaDefinition for TimeDelta {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        <(i64, i32)>::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+
+    #[test]
+    fn date_time() {
+        let utc = Utc.with_ymd_and_hms(2020, 1, 2, 3, 4, 5).unwrap();
+        let offset = FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2020, 1, 2, 3, 4, 5)
+            .unwrap();
+
+        assert_eq!(
+            DateTime::<Utc>::get_json_schema_definition(),
+            json!({ "type": "string", "format": "date-time" })
+        );
+        assert_eq!(serde_json::to_value(utc).unwrap(), "2020-01-02T03:04:05Z");
+        assert_eq!(
+            serde_json::to_value(offset).unwrap(),
+            "2020-01-02T03:04:05+01:00"
+        );
+    }
+
+    #[test]
+    fn naive() {
+        let date = NaiveDate::from_ymd_opt(2020, 1, 2).unwrap();
+        let time = NaiveTime::from_hms_milli_opt(3, 4, 5, 6).unwrap();
+
+        assert_eq!(
+            NaiveDate::get_json_schema_definition(),
+            json!({ "type": "string", "format": "date" })
+        );
+        assert_eq!(serde_json::to_value(date).unwrap(), "2020-01-02");
+        assert_eq!(
+            NaiveTime::get_json_schema_definition(),
+            json!({
+                "type": "string",
+                "format": "time",
+                "pattern": r"^\d{2}:\d{2}:\d{2}(\.\d+)?$",
+            })
+        );
+        assert_eq!(serde_json::to_value(time).unwrap(), "03:04:05.006");
+        assert_eq!(
+            serde_json::to_value(date.and_time(time)).unwrap(),
+            "2020-01-02T03:04:05.006"
+        );
+    }
+
+    #[test]
+    fn duration() {
+        assert_eq!(
+            TimeDelta::get_json_schema_definition(),
+            <(i64, i32)>::get_json_schema_definition()
+        );
+        assert_eq!(
+            serde_json::to_value(TimeDelta::milliseconds(1500)).unwrap(),
+            json!([1, 500_000_000])
+        );
+    }
+}
@@ -1,31 +1,66 @@
-use crate::JsonSchemaDefinition;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use crate::{JsonSchemaDefinition, ParameterObject, SchemaGenerator};
+
+/// Which of `minimum`/`maximum` a number schema keeps when the generator does
+/// not ask for full bounds: only the ones the `format` does not already imply.
+enum Bounds {
+    Both,
+    Minimum,
+    Implied,
+}
 
 macro_rules! impl_json_schema_definition {
-    ($rt:ty, "integer", $min:expr, $max:expr) => {
-        impl JsonSchemaDefinition for $rt {
-            fn get_json_schema_definition() -> serde_json::Value {
-                json!({
-                    "type": "integer",
-                    "minimum": $min,
-                    "maximum": $max,
-                })
-            }
-        }
-    };
-    ($rt:ty, "number", $min:expr, $max:expr) => {
+    ($rt:ty, $type:tt, $format:expr, $bounds:ident) => {
         impl JsonSchemaDefinition for $rt {
-            fn get_json_schema_definition() -> serde_json::Value {
-                json!({
-                    "type": "number",
-                    "minimum": $min,
-                    "maximum": $max,
-                })
+            fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+                let wide = std::mem::size_of::<$rt>() >= 8;
+                let mut schema = if $type == "integer" && generator.integer_as_string(wide) {
+                    // Written with `Display`: digits, after a sign for signed integers
+                    let pattern = if <$rt>::MIN == 0 as $rt {
+                        "^[0-9]+$"
+                    } else {
+                        "^-?[0-9]+$"
+                    };
+                    json!({ "type": "string", "pattern": pattern })
+                } else {
+                    // 128-bit bounds don't all fit in a serde_json number
+                    let bound = |value: $rt| {
+                        serde_json::to_value(value).unwrap_or_else(|_| json!(value as f64))
+                    };
+                    let mut schema = json!({ "type": $type });
+                    match (generator.settings().numeric_bounds, Bounds::$bounds) {
+                        (true, _) | (false, Bounds::Both) => {
+                            schema["minimum"] = bound(<$rt>::MIN);
+                            schema["maximum"] = bound(<$rt>::MAX);
+                        }
+                        (false, Bounds::Minimum) => schema["minimum"] = bound(<$rt>::MIN),
+                        (false, Bounds::Implied) => {}
+                    }
+                    schema
+                };
+                let format: Option<&str> = $format;
+                if let Some(format) = format {
+                    schema["format"] = json!(format);
+                }
+                schema
             }
         }
     };
     ($rt:ty, "boolean") => {
         impl JsonSchemaDefinition for $rt {
-            fn get_json_schema_definition() -> serde_json::Value {
+            fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
                 json!({
                     "type": "boolean"
                 })
@@ -34,97 +69,406 @@ macro_rules! impl_json_schema_definition {
     };
     ($rt:ty, "string") => {
         impl JsonSchemaDefinition for $rt {
-            fn get_json_schema_definition() -> serde_json::Value {
+            fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
                 json!({
                     "type": "string",
                 })
             }
         }
     };
-    ($rt:ty, "array", "integer", $min:expr, $max:expr) => {
-        impl JsonSchemaDefinition for $rt {
-            fn get_json_schema_definition() -> serde_json::Value {
-                json!({
-                    "type": "array",
-                    "items": {
-                        "type": "integer",
-                        "minimum": $min,
-                        "maximum": $max,
-                    },
-                })
-            }
-        }
-    };
 }
-impl_json_schema_definition!(i8, "integer", std::i8::MIN, std::i8::MAX);
-impl_json_schema_definition!(i16, "integer", std::i16::MIN, std::i16::MAX);
-impl_json_schema_definition!(i32, "integer", std::i32::MIN, std::i32::MAX);
-impl_json_schema_definition!(i64, "integer", std::i64::MIN, std::i64::MAX);
-impl_json_schema_definition!(i128, "integer", std::i128::MIN, std::i128::MAX);
-impl_json_schema_definition!(u8, "integer", std::u8::MIN, std::u8::MAX);
-impl_json_schema_definition!(u16, "integer", std::u16::MIN, std::u16::MAX);
-impl_json_schema_definition!(u32, "integer", std::u32::MIN, std::u32::MAX);
-impl_json_schema_definition!(u64, "integer", std::u64::MIN, std::u64::MAX);
-impl_json_schema_definition!(u128, "integer", std::u128::MIN, std::u128::MAX);
-impl_json_schema_definition!(isize, "integer", std::isize::MIN, std::isize::MAX);
-impl_json_schema_definition!(usize, "integer", std::usize::MIN, std::usize::MAX);
-
-impl_json_schema_definition!(f32, "number", std::f32::MIN, std::f32::MAX);
-impl_json_schema_definition!(f64, "number", std::f64::MIN, std::f64::MAX);
+// The formats of the OpenAPI data types table: int64 is signed, and there is
+// none wider, so none fits the unsigned 64-bit integers
+impl_json_schema_definition!(i8, "integer", Some("int32"), Both);
+impl_json_schema_definition!(i16, "integer", Some("int32"), Both);
+impl_json_schema_definition!(i32, "integer", Some("int32"), Implied);
+impl_json_schema_definition!(i64, "integer", Some("int64"), Implied);
+impl_json_schema_definition!(i128, "integer", None, Implied);
+impl_json_schema_definition!(isize, "integer", Some("int64"), Implied);
+impl_json_schema_definition!(u8, "integer", Some("int32"), Both);
+impl_json_schema_definition!(u16, "integer", Some("int32"), Both);
+impl_json_schema_definition!(u32, "integer", Some("int64"), Both);
+impl_json_schema_definition!(u64, "integer", None, Minimum);
+impl_json_schema_definition!(u128, "integer", None, Minimum);
+impl_json_schema_definition!(usize, "integer", None, Minimum);
+
+impl_json_schema_definition!(f32, "number", Some("float"), Implied);
+impl_json_schema_definition!(f64, "number", Some("double"), Implied);
 
 impl_json_schema_definition!(bool, "boolean");
 
 impl_json_schema_definition!(String, "string");
-impl_json_schema_definition!(&str, "string");
-
-impl_json_schema_definition!([i8], "array", "integer", std::i8::MIN, std::i8::MAX);
-impl_json_schema_definition!([i16], "array", "integer", std::i16::MIN, std::i16::MAX);
-impl_json_schema_definition!([i32], "array", "integer", std::i32::MIN, std::i32::MAX);
-impl_json_schema_definition!([i64], "array", "integer", std::i64::MIN, std::i64::MAX);
-impl_json_schema_definition!([i128], "array", "integer", std::i128::MIN, std::i128::MAX);
-impl_json_schema_definition!([u8], "array", "integer", std::u8::MIN, std::u8::MAX);
-impl_json_schema_definition!([u16], "array", "integer", std::u16::MIN, std::u16::MAX);
-impl_json_schema_definition!([u32], "array", "integer", std::u32::MIN, std::u32::MAX);
-impl_json_schema_definition!([u64], "array", "integer", std::u64::MIN, std::u64::MAX);
-impl_json_schema_definition!([u128], "array", "integer", std::u128::MIN, std::u128::MAX);
-impl_json_schema_definition!(
-    [isize],
-    "array",
-    "integer",
-    std::isize::MIN,
-    std::isize::MAX
-);
-impl_json_schema_definition!(
-    [usize],
-    "array",
-    "integer",
-    std::usize::MIN,
-    std::usize::MAX
-);
+impl_json_schema_definition!(str, "string");
+impl_json_schema_definition!(Path, "string");
+impl_json_schema_definition!(PathBuf, "string");
+
+// A single Unicode scalar value, which JSON Schema counts as one character
+impl JsonSchemaDefinition for char {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "minLength": 1,
+            "maxLength": 1,
+        })
+    }
+}
+
+/// `schema`, the schema of an integer, without 0.
+fn non_zero_schema(schema: serde_json::Value, signed: bool) -> serde_json::Value {
+    let keywords = match (schema["type"].as_str(), signed) {
+        (Some("string"), false) => json!({ "pattern": "^[1-9][0-9]*$" }),
+        (Some("string"), true) => json!({ "pattern": "^-?[1-9][0-9]*$" }),
+        (_, false) => json!({ "minimum": 1 }),
+        (_, true) => json!({ "not": { "enum": [0] } }),
+    };
+    crate::schema::extend_schema(schema, keywords)
+}
+
+macro_rules! impl_non_zero {
+    ($($rt:ty => $int:ty, $signed:expr;)+) => {
+        $(
+            impl JsonSchemaDefinition for $rt {
+                fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+                    non_zero_schema(<$int>::json_schema(generator), $signed)
+                }
+            }
+        )+
+    };
+}
+impl_non_zero! {
+    NonZeroI8 => i8, true;
+    NonZeroI16 => i16, true;
+    NonZeroI32 => i32, true;
+    NonZeroI64 => i64, true;
+    NonZeroI128 => i128, true;
+    NonZeroIsize => isize, true;
+    NonZeroU8 => u8, false;
+    NonZeroU16 => u16, false;
+    NonZeroU32 => u32, false;
+    NonZeroU64 => u64, false;
+    NonZeroU128 => u128, false;
+    NonZeroUsize => usize, false;
+}
+
+impl JsonSchemaDefinition for Ipv4Addr {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "format": "ipv4",
+        })
+    }
+}
+
+impl JsonSchemaDefinition for Ipv6Addr {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "format": "ipv6",
+        })
+    }
+}
+
+impl JsonSchemaDefinition for IpAddr {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "oneOf": [
+                Ipv4Addr::json_schema(generator),
+                Ipv6Addr::json_schema(generator),
+            ],
+        })
+    }
+}
+
+// `address:port`, the IPv6 address, and its scope id, in brackets
+impl JsonSchemaDefinition for SocketAddrV4 {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "pattern": r"^[0-9]{1,3}(\.[0-9]{1,3}){3}:[0-9]{1,5}$",
+        })
+    }
+}
+
+impl JsonSchemaDefinition for SocketAddrV6 {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "pattern": r"^\[[0-9a-fA-F:.]+(%[0-9]+)?\]:[0-9]{1,5}$",
+        })
+    }
+}
+
+impl JsonSchemaDefinition for SocketAddr {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "oneOf": [
+                SocketAddrV4::json_schema(generator),
+                SocketAddrV6::json_schema(generator),
+            ],
+        })
+    }
+}
+
+impl JsonSchemaDefinition for Duration {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "required": ["secs", "nanos"],
+            "properties": {
+                "secs": generator.subschema_for::<u64>(),
+                "nanos": generator.subschema_for::<u32>(),
+            },
+        })
+    }
+}
+
+// The time elapsed since the Unix epoch
+impl JsonSchemaDefinition for SystemTime {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "required": ["secs_since_epoch", "nanos_since_epoch"],
+            "properties": {
+                "secs_since_epoch": generator.subschema_for::<u64>(),
+                "nanos_since_epoch": generator.subschema_for::<u32>(),
+            },
+        })
+    }
+}
+
+// Any JSON value
+impl JsonSchemaDefinition for serde_json::Value {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({})
+    }
+}
+
+impl JsonSchemaDefinition for serde_json::Map<String, serde_json::Value> {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "additionalProperties": true,
+        })
+    }
+}
+
+impl JsonSchemaDefinition for serde_json::Number {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "number",
+        })
+    }
+}
+
+// `()` is serialized as `null`
+impl JsonSchemaDefinition for () {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        generator.null_schema()
+    }
+}
 
 impl<T: JsonSchemaDefinition> JsonSchemaDefinition for Option<T> {
-    fn get_json_schema_definition() -> serde_json::Value {
-        <T>::get_json_schema_definition()
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        let schema = generator.subschema_for::<T>();
+        if generator.settings().option_nullable {
+            generator.nullable_schema(schema)
+        } else {
+            schema
+        }
+    }
+
+    // Deserialized as `None` when its fields are all missing
+    fn flattened_query_definitions(generator: &mut SchemaGenerator) -> Vec<ParameterObject> {
+        T::flattened_query_definitions(generator)
+            .into_iter()
+            .map(|mut parameter| {
+                parameter.required = Some(false);
+                parameter
+            })
+            .collect()
     }
 }
 
+// Serialized exactly like the value they hold
+macro_rules! impl_transparent {
+    ($([$($param:tt)+] $rt:ty;)+) => {
+        $(
+            impl<$($param)+> JsonSchemaDefinition for $rt {
+                fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+                    T::json_schema(generator)
+                }
+
+                fn schema_name() -> Option<String> {
+                    T::schema_name()
+                }
+
+                fn flattened_query_definitions(
+                    generator: &mut SchemaGenerator,
+                ) -> Vec<ParameterObject> {
+                    T::flattened_query_definitions(generator)
+                }
+            }
+        )+
+    };
+}
+impl_transparent! {
+    ['a, T: JsonSchemaDefinition + ?Sized] &'a T;
+    ['a, T: JsonSchemaDefinition + ?Sized] &'a mut T;
+    [T: JsonSchemaDefinition + ?Sized] Box<T>;
+    [T: JsonSchemaDefinition + ?Sized] Rc<T>;
+    [T: JsonSchemaDefinition + ?Sized] Arc<T>;
+    ['a, T: JsonSchemaDefinition + ToOwned + ?Sized] Cow<'a, T>;
+    [T: JsonSchemaDefinition + ?Sized] RefCell<T>;
+    [T: JsonSchemaDefinition + ?Sized] Mutex<T>;
+    [T: JsonSchemaDefinition + ?Sized] RwLock<T>;
+    [T: JsonSchemaDefinition] Wrapping<T>;
+}
+
+fn array_schema<T: JsonSchemaDefinition>(generator: &mut SchemaGenerator) -> serde_json::Value {
+    json!({
+        "type": "array",
+        "items": generator.subschema_for::<T>(),
+    })
+}
+
+fn set_schema<T: JsonSchemaDefinition>(generator: &mut SchemaGenerator) -> serde_json::Value {
+    json!({
+        "type": "array",
+        "items": generator.subschema_for::<T>(),
+        "uniqueItems": true,
+    })
+}
+
 impl<T: JsonSchemaDefinition> JsonSchemaDefinition for Vec<T> {
-    fn get_json_schema_definition() -> serde_json::Value {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        array_schema::<T>(generator)
+    }
+}
+
+impl<T: JsonSchemaDefinition> JsonSchemaDefinition for VecDeque<T> {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        array_schema::<T>(generator)
+    }
+}
+
+impl<T: JsonSchemaDefinition> JsonSchemaDefinition for LinkedList<T> {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        array_schema::<T>(generator)
+    }
+}
+
+impl<T: JsonSchemaDefinition> JsonSchemaDefinition for [T] {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        array_schema::<T>(generator)
+    }
+}
+
+impl<T: JsonSchemaDefinition, const N: usize> JsonSchemaDefinition for [T; N] {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
         json!({
             "type": "array",
-            "items": T::get_json_schema_definition(),
+            "items": generator.subschema_for::<T>(),
+            "minItems": N,
+            "maxItems": N,
         })
     }
 }
 
+impl<T: JsonSchemaDefinition, S> JsonSchemaDefinition for HashSet<T, S> {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        set_schema::<T>(generator)
+    }
+}
+
+impl<T: JsonSchemaDefinition> JsonSchemaDefinition for BTreeSet<T> {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        set_schema::<T>(generator)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<T: JsonSchemaDefinition, S> JsonSchemaDefinition for indexmap::IndexSet<T, S> {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        set_schema::<T>(generator)
+    }
+}
+
+// Tuples are serialized as arrays with one item per position
+macro_rules! impl_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: JsonSchemaDefinition),+> JsonSchemaDefinition for ($($name,)+) {
+            fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+                let items = vec![$(generator.subschema_for::<$name>()),+];
+                generator.tuple_schema(items)
+            }
+        }
+    };
+}
+impl_tuple!(T0);
+impl_tuple!(T0 T1);
+impl_tuple!(T0 T1 T2);
+impl_tuple!(T0 T1 T2 T3);
+impl_tuple!(T0 T1 T2 T3 T4);
+impl_tuple!(T0 T1 T2 T3 T4 T5);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
+
+/// The schema of a map serialized as a JSON object: the keys become property
+/// names, so only keys serialized as strings (strings, integers, unit enums)
+/// make sense. The variants of an enum key are the only allowed property names.
+fn map_schema<K, V>(generator: &mut SchemaGenerator) -> serde_json::Value
+where
+    K: JsonSchemaDefinition + ?Sized,
+    V: JsonSchemaDefinition,
+{
+    let key = K::json_schema(generator);
+    let mut schema = json!({
+        "type": "object",
+        "additionalProperties": generator.subschema_for::<V>(),
+    });
+    if let Some(variants) = key.get("enum") {
+        schema["propertyNames"] = json!({ "enum": variants });
+    }
+    schema
+}
+
+impl<K: JsonSchemaDefinition, V: JsonSchemaDefinition, S> JsonSchemaDefinition
+    for HashMap<K, V, S>
+{
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        map_schema::<K, V>(generator)
+    }
+}
+
+impl<K: JsonSchemaDefinition, V: JsonSchemaDefinition> JsonSchemaDefinition for BTreeMap<K, V> {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        map_schema::<K, V>(generator)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K: JsonSchemaDefinition, V: JsonSchemaDefinition, S> JsonSchemaDefinition
+    for indexmap::IndexMap<K, V, S>
+{
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        map_schema::<K, V>(generator)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::assert_matches_pattern;
+    use crate::swagger_object::SwaggerVersion;
     use crate::Field;
 
     impl PartialEq for Field {
         fn eq(&self, other: &Self) -> bool {
-            self.name == other.name
+            self.attrs.name == other.attrs.name
         }
     }
 
@@ -141,6 +485,7 @@ mod tests {
             u8,
             json!({
                 "type": "integer",
+                "format": "int32",
                 "minimum": 0,
                 "maximum": 255,
             })
@@ -153,6 +498,7 @@ mod tests {
             i8,
             json!({
                 "type": "integer",
+                "format": "int32",
                 "minimum": -128,
                 "maximum": 127,
             })
@@ -160,29 +506,116 @@ mod tests {
     }
 
     #[test]
-    fn get_json_schema_definition_f32() {
+    fn get_json_schema_definition_integer_formats() {
+        test!(i32, json!({ "type": "integer", "format": "int32" }));
+        test!(i64, json!({ "type": "integer", "format": "int64" }));
+        test!(i128, json!({ "type": "integer" }));
         test!(
-            f32,
+            u32,
             json!({
-                "type": "number",
-                "minimum": -340_282_346_638_528_860_000_000_000_000_000_000_000.0,
-                "maximum": 340_282_346_638_528_860_000_000_000_000_000_000_000.0,
+                "type": "integer",
+                "format": "int64",
+                "minimum": 0,
+                "maximum": 4_294_967_295u32,
             })
         );
+        // int64 is signed: no format fits the unsigned 64-bit integers
+        test!(u64, json!({ "type": "integer", "minimum": 0 }));
+        test!(usize, json!({ "type": "integer", "minimum": 0 }));
+        test!(u128, json!({ "type": "integer", "minimum": 0 }));
+    }
+
+    #[test]
+    fn get_json_schema_definition_f32() {
+        test!(f32, json!({ "type": "number", "format": "float" }));
     }
 
     #[test]
     fn get_json_schema_definition_f64() {
-        test!(
-            f64,
+        test!(f64, json!({ "type": "number", "format": "double" }));
+    }
+
+    #[test]
+    fn get_json_schema_definition_numeric_bounds() {
+        let mut generator = SchemaGenerator::new(crate::SchemaSettings {
+            numeric_bounds: true,
+            ..crate::SchemaSettings::default()
+        });
+
+        assert_eq!(
+            i64::json_schema(&mut generator),
+            json!({
+                "type": "integer",
+                "format": "int64",
+                "minimum": i64::MIN,
+                "maximum": i64::MAX,
+            })
+        );
+        assert_eq!(
+            u128::json_schema(&mut generator),
+            json!({
+                "type": "integer",
+                "minimum": 0,
+                "maximum": 340_282_366_920_938_463_463_374_607_431_768_211_455.0,
+            })
+        );
+        assert_eq!(
+            f32::json_schema(&mut generator),
+            json!({
+                "type": "number",
+                "format": "float",
+                "minimum": -340_282_346_638_528_860_000_000_000_000_000_000_000.0,
+                "maximum": 340_282_346_638_528_860_000_000_000_000_000_000_000.0,
+            })
+        );
+        assert_eq!(
+            f64::json_schema(&mut generator),
             json!({
                 "type": "number",
+                "format": "double",
                 "minimum": -179_769_313_486_231_570_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000.0,
                 "maximum": 179_769_313_486_231_570_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000.0,
             })
         );
     }
 
+    #[test]
+    fn get_json_schema_definition_integers_as_strings() {
+        let mut generator = SchemaGenerator::new(crate::SchemaSettings {
+            integers_as_strings: true,
+            ..crate::SchemaSettings::default()
+        });
+
+        assert_eq!(
+            i64::json_schema(&mut generator),
+            json!({ "type": "string", "format": "int64", "pattern": "^-?[0-9]+$" })
+        );
+        assert_eq!(
+            u128::json_schema(&mut generator),
+            json!({ "type": "string", "pattern": "^[0-9]+$" })
+        );
+        assert_matches_pattern(
+            &i64::json_schema(&mut generator),
+            &json!(i64::MIN.to_string()),
+        );
+        assert_matches_pattern(
+            &u128::json_schema(&mut generator),
+            &json!(u128::MAX.to_string()),
+        );
+        assert_eq!(
+            u32::json_schema(&mut generator),
+            u32::get_json_schema_definition()
+        );
+        assert_eq!(
+            generator.with_integers_as_strings(false, i64::json_schema),
+            json!({ "type": "integer", "format": "int64" })
+        );
+        assert_eq!(
+            SchemaGenerator::default().with_integers_as_strings(true, u8::json_schema),
+            json!({ "type": "string", "format": "int32", "pattern": "^[0-9]+$" })
+        );
+    }
+
     #[test]
     fn get_json_schema_definition_boolean() {
         test!(
@@ -213,6 +646,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_json_schema_definition_unit() {
+        test!(
+            (),
+            json!({
+                "nullable": true,
+                "enum": [null],
+            })
+        );
+    }
+
     #[test]
     fn get_json_schema_definition_option() {
         test!(
@@ -222,4 +666,226 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn get_json_schema_definition_map() {
+        test!(
+            HashMap<String, bool>,
+            json!({
+                "type": "object",
+                "additionalProperties": { "type": "boolean" },
+            })
+        );
+        test!(
+            BTreeMap<u32, String>,
+            json!({
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+            })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_sequences() {
+        let bytes = json!({
+            "type": "array",
+            "items": { "type": "integer", "format": "int32", "minimum": 0, "maximum": 255 },
+        });
+        test!([u8], bytes);
+        test!(VecDeque<u8>, bytes);
+        test!(LinkedList<u8>, bytes);
+        test!(
+            BTreeSet<String>,
+            json!({
+                "type": "array",
+                "items": { "type": "string" },
+                "uniqueItems": true,
+            })
+        );
+        test!(
+            [bool; 3],
+            json!({
+                "type": "array",
+                "items": { "type": "boolean" },
+                "minItems": 3,
+                "maxItems": 3,
+            })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_tuple() {
+        let mut v310 = SchemaGenerator::new(crate::SchemaSettings {
+            version: SwaggerVersion::V310,
+            ..crate::SchemaSettings::default()
+        });
+
+        test!(
+            (String, bool),
+            json!({
+                "type": "array",
+                "items": { "anyOf": [{ "type": "string" }, { "type": "boolean" }] },
+                "minItems": 2,
+                "maxItems": 2,
+            })
+        );
+        assert_eq!(
+            <(String, bool)>::json_schema(&mut v310),
+            json!({
+                "type": "array",
+                "prefixItems": [{ "type": "string" }, { "type": "boolean" }],
+                "minItems": 2,
+                "maxItems": 2,
+            })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_smart_pointers() {
+        let string = json!({ "type": "string" });
+        test!(str, string);
+        test!(Cow<'static, str>, string);
+        test!(Arc<String>, string);
+        test!(Rc<RefCell<String>>, string);
+        test!(Mutex<String>, string);
+        test!(
+            Wrapping<u8>,
+            json!({ "type": "integer", "format": "int32", "minimum": 0, "maximum": 255 })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_char_and_path() {
+        test!(
+            char,
+            json!({ "type": "string", "minLength": 1, "maxLength": 1 })
+        );
+        assert_eq!(serde_json::to_value('é').unwrap(), "é");
+        test!(PathBuf, json!({ "type": "string" }));
+        assert_eq!(
+            serde_json::to_value(PathBuf::from("/tmp/file")).unwrap(),
+            "/tmp/file"
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_non_zero() {
+        test!(
+            NonZeroU8,
+            json!({ "type": "integer", "format": "int32", "minimum": 1, "maximum": 255 })
+        );
+        test!(
+            NonZeroI64,
+            json!({ "type": "integer", "format": "int64", "not": { "enum": [0] } })
+        );
+        assert_eq!(
+            serde_json::to_value(NonZeroI64::new(-3).unwrap()).unwrap(),
+            json!(-3)
+        );
+
+        let mut generator = SchemaGenerator::new(crate::SchemaSettings {
+            integers_as_strings: true,
+            ..crate::SchemaSettings::default()
+        });
+        assert_eq!(
+            NonZeroU64::json_schema(&mut generator),
+            json!({ "type": "string", "pattern": "^[1-9][0-9]*$" })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_ip_addr() {
+        let schema = IpAddr::get_json_schema_definition();
+
+        assert_eq!(
+            schema,
+            json!({
+                "oneOf": [
+                    { "type": "string", "format": "ipv4" },
+                    { "type": "string", "format": "ipv6" },
+                ],
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(IpAddr::from([127, 0, 0, 1])).unwrap(),
+            "127.0.0.1"
+        );
+        assert_eq!(
+            serde_json::to_value(IpAddr::from(Ipv6Addr::LOCALHOST)).unwrap(),
+            "::1"
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_socket_addr() {
+        let schema = SocketAddr::get_json_schema_definition();
+        let v4 = &schema["oneOf"][0];
+        let v6 = &schema["oneOf"][1];
+
+        assert_matches_pattern(
+            v4,
+            &serde_json::to_value(SocketAddr::from(([127, 0, 0, 1], 8080))).unwrap(),
+        );
+        assert_matches_pattern(
+            v6,
+            &serde_json::to_value(SocketAddr::from((Ipv6Addr::LOCALHOST, 443))).unwrap(),
+        );
+        assert_matches_pattern(
+            v6,
+            &serde_json::to_value(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 2)).unwrap(),
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_duration() {
+        let u64_schema = u64::get_json_schema_definition();
+        let u32_schema = u32::get_json_schema_definition();
+
+        test!(
+            Duration,
+            json!({
+                "type": "object",
+                "required": ["secs", "nanos"],
+                "properties": { "secs": u64_schema, "nanos": u32_schema },
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(Duration::from_millis(1500)).unwrap(),
+            json!({ "secs": 1, "nanos": 500_000_000 })
+        );
+        test!(
+            SystemTime,
+            json!({
+                "type": "object",
+                "required": ["secs_since_epoch", "nanos_since_epoch"],
+                "properties": { "secs_since_epoch": u64_schema, "nanos_since_epoch": u32_schema },
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(SystemTime::UNIX_EPOCH + Duration::from_secs(60)).unwrap(),
+            json!({ "secs_since_epoch": 60, "nanos_since_epoch": 0 })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_json_value() {
+        test!(serde_json::Value, json!({}));
+        test!(
+            serde_json::Map<String, serde_json::Value>,
+            json!({ "type": "object", "additionalProperties": true })
+        );
+        test!(serde_json::Number, json!({ "type": "number" }));
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn get_json_schema_definition_index_map() {
+        test!(
+            indexmap::IndexMap<String, bool>,
+            json!({
+                "type": "object",
+                "additionalProperties": { "type": "boolean" },
+            })
+        );
+    }
 }
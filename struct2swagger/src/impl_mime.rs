@@ -0,0 +1,43 @@
+//! The schema of `mime::Mime`.
+//!
+//! mime has no serde support: a `Mime` is serialized through its `Display`,
+//! as `mime_serde_shim` or serde_with's `DisplayFromStr` do.
+
+use mime::Mime;
+
+use crate::{JsonSchemaDefinition, SchemaGenerator};
+
+/// `type/subtype`, made of the token characters of RFC 7230, and its parameters.
+const MIME_PATTERN: &str =
+    r"^[!#$%&'*+.^_`|~0-9A-Za-z-]+/[!#$%&'*+.^_`|~0-9A-Za-z-]+(\s*;\s*[^;]+)*$";
+
+impl JsonSchemaDefinition for Mime {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "pattern": MIME_PATTERN,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::assert_matches_pattern;
+
+    #[test]
+    fn mime() {
+        let schema = Mime::get_json_schema_definition();
+
+        for mime in &[
+            mime::APPLICATION_JSON,
+            mime::TEXT_PLAIN_UTF_8,
+            "application/vnd.api+json".parse().unwrap(),
+            "multipart/form-data; boundary=abc".parse().unwrap(),
+        ] {
+            let value = serde_json::to_value(mime.to_string()).unwrap();
+            assert_matches_pattern(&schema, &value);
+        }
+        assert!(!regex::Regex::new(MIME_PATTERN).unwrap().is_match("json"));
+    }
+}
@@ -0,0 +1,57 @@
+//! The schemas of `num_bigint::BigInt` and `BigUint`, as serialized by
+//! num-bigint's `serde` feature: not as numbers, but as their base 2^32 digits.
+
+use num_bigint::{BigInt, BigUint};
+
+use crate::{JsonSchemaDefinition, SchemaGenerator};
+
+// The digits, least significant first
+impl JsonSchemaDefinition for BigUint {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "array",
+            "items": generator.subschema_for::<u32>(),
+        })
+    }
+}
+
+// `[sign, digits]`, the sign being -1, 0 or 1
+impl JsonSchemaDefinition for BigInt {
+    fn json_schema(generator: &mut SchemaGenerator) -> serde_json::Value {
+        let sign = json!({ "type": "integer", "enum": [-1, 0, 1] });
+        let digits = generator.subschema_for::<BigUint>();
+        generator.tuple_schema(vec![sign, digits])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn big_int() {
+        let digits = json!({
+            "type": "array",
+            "items": u32::get_json_schema_definition(),
+        });
+
+        assert_eq!(BigUint::get_json_schema_definition(), digits);
+        assert_eq!(
+            BigInt::get_json_schema_definition(),
+            json!({
+                "type": "array",
+                "items": { "anyOf": [{ "type": "integer", "enum": [-1, 0, 1] }, digits] },
+                "minItems": 2,
+                "maxItems": 2,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(BigInt::from(-5_000_000_000i64)).unwrap(),
+            json!([-1, [705_032_704, 1]])
+        );
+        assert_eq!(
+            serde_json::to_value(BigUint::from(7u8)).unwrap(),
+            json!([7])
+        );
+    }
+}
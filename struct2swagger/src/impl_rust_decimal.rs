@@ -0,0 +1,57 @@
+//! The schema of `rust_decimal::Decimal`.
+//!
+//! serde writes a `Decimal` as a string, unless rust_decimal's `serde-float`
+//! or `serde-arbitrary-precision` features make it a number. Features are
+//! unified across the whole build, so which one is on can't be told from
+//! here: the schema follows what serde writes for a sample value.
+
+use rust_decimal::Decimal;
+use serde_json::Value;
+
+use crate::{JsonSchemaDefinition, SchemaGenerator};
+
+const DECIMAL_PATTERN: &str = r"^-?[0-9]+(\.[0-9]+)?$";
+
+/// The schema of a `Decimal`, `sample` being what serde writes for one.
+fn decimal_schema(sample: Option<Value>) -> Value {
+    match sample {
+        Some(Value::Number(_)) => json!({ "type": "number" }),
+        _ => json!({
+            "type": "string",
+            "pattern": DECIMAL_PATTERN,
+        }),
+    }
+}
+
+impl JsonSchemaDefinition for Decimal {
+    fn json_schema(_: &mut SchemaGenerator) -> Value {
+        decimal_schema(serde_json::to_value(Decimal::ONE).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::assert_matches_pattern;
+    use std::str::FromStr;
+
+    #[test]
+    fn decimal() {
+        let schema = Decimal::get_json_schema_definition();
+
+        assert_eq!(schema["type"], "string");
+        for decimal in &["1.50", "-0.000001", "79228162514264337593543950335"] {
+            let value = serde_json::to_value(Decimal::from_str(decimal).unwrap()).unwrap();
+            assert_eq!(value, *decimal);
+            assert_matches_pattern(&schema, &value);
+        }
+    }
+
+    #[test]
+    fn decimal_as_number() {
+        assert_eq!(
+            decimal_schema(Some(json!(1.0))),
+            json!({ "type": "number" })
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! The schema of `semver::Version`, written as a string by semver's `serde` feature.
+
+use semver::Version;
+
+use crate::{JsonSchemaDefinition, SchemaGenerator};
+
+/// The regular expression given by the Semantic Versioning specification.
+const VERSION_PATTERN: &str = concat!(
+    r"^(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)",
+    r"(?:-((?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*)(?:\.(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*))*))?",
+    r"(?:\+([0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*))?$",
+);
+
+impl JsonSchemaDefinition for Version {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "pattern": VERSION_PATTERN,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::assert_matches_pattern;
+
+    #[test]
+    fn version() {
+        let schema = Version::get_json_schema_definition();
+
+        for version in &[
+            "0.1.0",
+            "1.2.3-alpha.1",
+            "1.0.0-rc.1+build.5",
+            "10.20.30+x86",
+        ] {
+            let value = serde_json::to_value(Version::parse(version).unwrap()).unwrap();
+            assert_eq!(value, *version);
+            assert_matches_pattern(&schema, &value);
+        }
+        assert!(!regex::Regex::new(VERSION_PATTERN).unwrap().is_match("1.2"));
+    }
+}
@@ -1,322 +1,737 @@
-use crate::quote::ToTokens;
-use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
-use std::iter::FromIterator;
-use syn::{Data, DeriveInput, Fields};
-
+use proc_macro2::TokenStream;
+use syn::{
+    parse_quote, Data, DataEnum, DeriveInput, Fields, FieldsNamed, GenericArgument, GenericParam,
+    PathArguments, Type, Variant,
+};
+
+use crate::attr::{self, RenameAllRules, TagType};
+use crate::ctxt::Ctxt;
 use crate::Field;
 
-fn get_fields(ast: &DeriveInput) -> Vec<Field> {
-    match &ast.data {
-        Data::Struct(s) => match &s.fields {
-            Fields::Named(named_fields) => {
-                let mut fields = vec![];
-
-                for field in named_fields.named.iter() {
-                    let field_name: String = field.ident.as_ref().unwrap().to_string().to_owned();
-                    let mut token_stream = TokenStream::new();
-                    field.ty.to_tokens(&mut token_stream);
-                    let field_tokens: Vec<TokenTree> = token_stream.into_iter().collect();
-
-                    fields.push(Field {
-                        name: field_name,
-                        ty: field_tokens,
-                    });
-                }
+fn get_named_fields(
+    cx: &Ctxt,
+    named_fields: &FieldsNamed,
+    rename_all: RenameAllRules,
+    container_default: bool,
+) -> Vec<Field> {
+    let mut fields = vec![];
+
+    for field in named_fields.named.iter() {
+        let attrs = attr::Field::from_ast(cx, field, rename_all, container_default);
+        if attrs.skip_serializing && attrs.skip_deserializing {
+            continue;
+        }
+
+        fields.push(Field {
+            attrs,
+            ty: field.ty.clone(),
+        });
+    }
+
+    fields
+}
 
-                fields
+/// The `T` of `ty` when it is an `Option<T>` at its top level, the only case
+/// serde treats as optional: `Vec<Option<u8>>` is not.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Group(group) => option_inner(&group.elem),
+        Type::Paren(paren) => option_inner(&paren.elem),
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            let path = &type_path.path;
+            let segments: Vec<String> = path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            let is_option_path = match segments.as_slice() {
+                [option] => option == "Option" && path.leading_colon.is_none(),
+                [krate, module, option] => {
+                    (krate == "std" || krate == "core") && module == "option" && option == "Option"
+                }
+                _ => false,
+            };
+            if !is_option_path {
+                return None;
             }
-            _ => unimplemented!("Only named struct is implemented. Please send PR!"),
-        },
-        _ => unimplemented!("Only struct is implemented. Please send PR!"),
+            match &path.segments.last().unwrap().arguments {
+                PathArguments::AngleBracketed(arguments) => match arguments.args.first() {
+                    Some(GenericArgument::Type(inner)) if arguments.args.len() == 1 => Some(inner),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
     }
 }
 
-fn get_struct_name(ast: &DeriveInput) -> String {
-    ast.ident.to_string()
+/// Whether `ty` is an `Option<_>` at its top level.
+fn is_option(ty: &Type) -> bool {
+    option_inner(ty).is_some()
 }
 
-fn contains_option(tt: &[TokenTree]) -> bool {
-    tt.iter().any(|t| match t {
-        TokenTree::Ident(ident) => *ident == "Option",
-        _ => false,
-    })
+/// Whether deserialization fails without the field.
+fn is_required_to_deserialize(field: &Field) -> bool {
+    field
+        .attrs
+        .required
+        .unwrap_or_else(|| !is_option(&field.ty) && !field.attrs.default)
 }
 
-fn get_json_schema_definition(
-    fields: &[Field],
-) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
-    let mut tokens = Vec::new();
+/// Whether the field is always present once serialized.
+fn is_always_serialized(field: &Field) -> bool {
+    field.attrs.required.unwrap_or_else(|| {
+        !is_option(&field.ty) && !field.attrs.default && !field.attrs.skip_serializing_if
+    })
+}
 
-    let mut properties = Vec::new();
-    for field in fields {
-        properties.push(TokenTree::Literal(Literal::string(&field.name)));
-        properties.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
+/// The title of a doc comment: its first line.
+fn doc_title(doc: &str) -> &str {
+    doc.lines().next().unwrap_or_default()
+}
 
-        properties.push(TokenTree::Punct(Punct::new('<', Spacing::Alone)));
+/// Adds the `title` and `description` taken from the doc comment to `schema`.
+fn with_doc(schema: TokenStream, doc: Option<&str>) -> TokenStream {
+    match doc {
+        Some(doc) => {
+            let title = doc_title(doc);
+            quote! {
+                _struct2swagger::schema::extend_schema(
+                    #schema,
+                    _struct2swagger::__private::json!({ "title": #title, "description": #doc }),
+                )
+            }
+        }
+        None => schema,
+    }
+}
 
-        properties.append(&mut field.ty.clone());
+/// Adds `keywords` to `schema`.
+fn extend_schema(schema: TokenStream, keywords: &[TokenStream]) -> TokenStream {
+    if keywords.is_empty() {
+        schema
+    } else {
+        quote! { _struct2swagger::schema::extend_schema(#schema, _struct2swagger::__private::json!({ #(#keywords,)* })) }
+    }
+}
 
-        properties.push(TokenTree::Punct(Punct::new('>', Spacing::Alone)));
+/// The keywords of `#[swagger(...)]` constraining the values of a field.
+fn constraint_keywords(schema: &attr::Schema) -> Vec<TokenStream> {
+    let mut keywords = vec![];
+    if let Some(example) = &schema.example {
+        keywords.push(quote! { "example": #example });
+    }
+    if let Some(format) = &schema.format {
+        keywords.push(quote! { "format": #format });
+    }
+    if let Some(minimum) = &schema.minimum {
+        keywords.push(quote! { "minimum": #minimum });
+    }
+    if let Some(maximum) = &schema.maximum {
+        keywords.push(quote! { "maximum": #maximum });
+    }
+    if let Some(min_length) = schema.min_length {
+        keywords.push(quote! { "minLength": #min_length });
+    }
+    if let Some(max_length) = schema.max_length {
+        keywords.push(quote! { "maxLength": #max_length });
+    }
+    if let Some(pattern) = &schema.pattern {
+        keywords.push(quote! { "pattern": #pattern });
+    }
+    if let Some(min_items) = schema.min_items {
+        keywords.push(quote! { "minItems": #min_items });
+    }
+    if let Some(max_items) = schema.max_items {
+        keywords.push(quote! { "maxItems": #max_items });
+    }
+    if let Some(unique_items) = schema.unique_items {
+        keywords.push(quote! { "uniqueItems": #unique_items });
+    }
+    keywords
+}
 
-        properties.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-        properties.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
+/// The schema of a field, with the keywords of `#[swagger(...)]`.
+fn constrained_schema(
+    schema: TokenStream,
+    attrs: &attr::Schema,
+    keywords: &[TokenStream],
+) -> TokenStream {
+    let schema = extend_schema(schema, keywords);
+    if attrs.nullable == Some(true) {
+        // How `null` is accepted depends on the OpenAPI version
+        quote! {
+            ({
+                let schema = #schema;
+                generator.nullable_schema(schema)
+            })
+        }
+    } else {
+        schema
+    }
+}
 
-        properties.push(TokenTree::Ident(Ident::new(
-            "get_json_schema_definition",
-            Span::call_site(),
-        )));
+/// The description given by `#[swagger(description = "...")]` or else by the doc comment.
+fn field_description(field: &Field) -> Option<&str> {
+    field
+        .attrs
+        .schema
+        .description
+        .as_deref()
+        .or(field.attrs.doc.as_deref())
+}
 
-        properties.push(TokenTree::Group(Group::new(
-            Delimiter::Parenthesis,
-            proc_macro2::TokenStream::new(),
-        )));
+/// The schema of the type of `field`, before any `#[swagger(...)]` keyword.
+fn field_type_schema(field: &Field) -> TokenStream {
+    let ty = &field.ty;
+    if field.attrs.any {
+        return quote! { _struct2swagger::__private::json!({}) };
+    }
+    match field.attrs.as_string {
+        Some(as_string) => quote! {
+            generator.with_integers_as_strings(#as_string, |generator| {
+                generator.subschema_for::<#ty>()
+            })
+        },
+        None => quote! { generator.subschema_for::<#ty>() },
+    }
+}
 
-        properties.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+/// The schema of the property holding `field`.
+fn field_schema(field: &Field) -> TokenStream {
+    let attrs = &field.attrs;
+    let write_only = attrs.schema.write_only.unwrap_or(attrs.skip_serializing);
+    let read_only = attrs
+        .schema
+        .read_only
+        .unwrap_or(!attrs.skip_serializing && attrs.skip_deserializing);
+
+    let mut keywords = vec![];
+    if write_only {
+        keywords.push(quote! { "writeOnly": true });
+    }
+    if read_only {
+        keywords.push(quote! { "readOnly": true });
+    }
+    if let Some(title) = attrs.doc.as_deref().map(doc_title) {
+        keywords.push(quote! { "title": #title });
+    }
+    if let Some(description) = field_description(field) {
+        keywords.push(quote! { "description": #description });
+    }
+    if attrs.schema.deprecated == Some(true) {
+        keywords.push(quote! { "deprecated": true });
     }
-    let properties = proc_macro2::TokenStream::from_iter(properties.into_iter());
+    keywords.extend(constraint_keywords(&attrs.schema));
 
-    tokens.push(TokenTree::Group(Group::new(Delimiter::Brace, properties)));
-    let tokens = proc_macro2::TokenStream::from_iter(tokens.into_iter());
+    constrained_schema(field_type_schema(field), &attrs.schema, &keywords)
+}
+
+fn get_json_schema_definition(fields: &[Field]) -> TokenStream {
+    object_schema(fields, None)
+}
 
-    let mut required_properties: Vec<TokenTree> = Vec::new();
-    for field in fields {
-        let has_option = contains_option(&field.ty);
+/// The schema of an object holding `fields`. `tag` is the `(property, value)`
+/// pair serde adds in front of the fields of an internally tagged variant.
+fn object_schema(fields: &[Field], tag: Option<(&str, &str)>) -> TokenStream {
+    let (flattened, fields): (Vec<&Field>, Vec<&Field>) =
+        fields.iter().partition(|field| field.attrs.flatten);
 
-        if has_option {
-            continue;
+    let tag_property = tag.map(|(property, value)| {
+        quote! {
+            #property: { "type": "string", "enum": [#value] }
         }
-        required_properties.push(TokenTree::Literal(Literal::string(&field.name)));
-        required_properties.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+    });
+    let properties = tag_property
+        .iter()
+        .cloned()
+        .chain(fields.iter().map(|field| {
+            let name = field.attrs.name.serialize_name();
+            let schema = field_schema(field);
+            quote! {
+                #name: #schema
+            }
+        }));
+
+    let required_properties: Vec<&str> = tag
+        .map(|(property, _)| property)
+        .into_iter()
+        .chain(
+            fields
+                .iter()
+                .filter(|field| is_always_serialized(field))
+                .map(|field| field.attrs.name.serialize_name()),
+        )
+        .collect();
+
+    let schema = if required_properties.is_empty() {
+        quote! {
+            _struct2swagger::__private::json!({
+                "type": "object",
+                "properties": { #(#properties,)* },
+            })
+        }
+    } else {
+        quote! {
+            _struct2swagger::__private::json!({
+                "type": "object",
+                "required": [ #(#required_properties,)* ],
+                "properties": { #(#properties,)* },
+            })
+        }
+    };
+
+    if flattened.is_empty() {
+        return schema;
     }
-    let required_properties = proc_macro2::TokenStream::from_iter(required_properties.into_iter());
 
-    (required_properties, tokens)
+    let flattened = flattened.iter().map(|field| {
+        // The fields of a flattened `Option<T>` are those of `T`, all optional
+        let ty = option_inner(&field.ty).unwrap_or(&field.ty);
+        let optional = !is_always_serialized(field);
+        quote! {
+            _struct2swagger::schema::flatten_schema(
+                &mut schema,
+                <#ty as _struct2swagger::JsonSchemaDefinition>::json_schema(generator),
+                #optional,
+            );
+        }
+    });
+
+    // In parentheses, so that `json!` takes the block for an expression
+    quote! {
+        ({
+            let mut schema = #schema;
+            #(#flattened)*
+            schema
+        })
+    }
 }
 
-fn get_query_definitions(fields: &[Field]) -> proc_macro2::TokenStream {
-    let mut query: Vec<TokenTree> = Vec::new();
-
-    for field in fields {
-        query.push(TokenTree::Ident(Ident::new(
-            "struct2swagger",
-            Span::call_site(),
-        )));
-        query.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-        query.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query.push(TokenTree::Ident(Ident::new(
-            "ParameterObject",
-            Span::call_site(),
-        )));
-
-        let mut query_field_description = Vec::<TokenTree>::new();
-
-        query_field_description.push(TokenTree::Ident(Ident::new("name", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Literal(Literal::string(&field.name)));
-        query_field_description.push(TokenTree::Punct(Punct::new('.', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("to_string", Span::call_site())));
-        query_field_description.push(TokenTree::Group(Group::new(
-            Delimiter::Parenthesis,
-            TokenStream::new(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new("where_in", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new(
-            "struct2swagger",
-            Span::call_site(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new(
-            "ParameterIn",
-            Span::call_site(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("Query", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new(
-            "description",
-            Span::call_site(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("None", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new("required", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("Some", Span::call_site())));
-        let required = {
-            let mut required = Vec::new();
-            let is_required = if contains_option(&field.ty) {
-                "false"
-            } else {
-                "true"
-            };
-            required.push(TokenTree::Ident(Ident::new(is_required, Span::call_site())));
-            proc_macro2::TokenStream::from_iter(required.into_iter())
-        };
-        query_field_description.push(TokenTree::Group(Group::new(
-            Delimiter::Parenthesis,
-            required,
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new(
-            "deprecated",
-            Span::call_site(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("None", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new(
-            "allow_empty_value",
-            Span::call_site(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("None", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new("schema", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("Some", Span::call_site())));
-        let schema_or_ref = {
-            let mut schema_or_ref = Vec::new();
-            schema_or_ref.push(TokenTree::Ident(Ident::new(
-                "struct2swagger",
-                Span::call_site(),
-            )));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-            schema_or_ref.push(TokenTree::Ident(Ident::new(
-                "swagger_object",
-                Span::call_site(),
-            )));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-            schema_or_ref.push(TokenTree::Ident(Ident::new(
-                "SchemaObjectOrReferenceObject",
-                Span::call_site(),
-            )));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-            schema_or_ref.push(TokenTree::Ident(Ident::new(
-                "SchemaObject",
-                Span::call_site(),
-            )));
-
-            let boxed = {
-                let mut boxed = Vec::new();
-
-                boxed.push(TokenTree::Ident(Ident::new("Box", Span::call_site())));
-                boxed.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-                boxed.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-                boxed.push(TokenTree::Ident(Ident::new("new", Span::call_site())));
-
-                let query_field_definition = {
-                    let mut query_field_definition = Vec::new();
-                    query_field_definition.push(TokenTree::Punct(Punct::new('<', Spacing::Alone)));
-                    query_field_definition.append(&mut field.ty.clone());
-                    query_field_definition.push(TokenTree::Punct(Punct::new('>', Spacing::Alone)));
-                    query_field_definition.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-                    query_field_definition.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-                    query_field_definition.push(TokenTree::Ident(Ident::new(
-                        "get_json_schema_definition",
-                        Span::call_site(),
-                    )));
-                    query_field_definition.push(TokenTree::Group(Group::new(
-                        Delimiter::Parenthesis,
-                        proc_macro2::TokenStream::new(),
-                    )));
-
-                    proc_macro2::TokenStream::from_iter(query_field_definition.into_iter())
-                };
+/// The schema of a fixed-length array, serde's representation of a tuple.
+fn tuple_schema(tys: &[&Type]) -> TokenStream {
+    quote! {
+        ({
+            let items = ::std::vec![ #(generator.subschema_for::<#tys>(),)* ];
+            generator.tuple_schema(items)
+        })
+    }
+}
 
-                boxed.push(TokenTree::Group(Group::new(
-                    Delimiter::Parenthesis,
-                    query_field_definition,
-                )));
+fn get_query_definitions(fields: &[Field]) -> TokenStream {
+    let query = fields
+        .iter()
+        .filter(|field| !field.attrs.skip_deserializing)
+        .map(|field| {
+            let ty = &field.ty;
+            let required = is_required_to_deserialize(field);
+
+            if field.attrs.flatten {
+                let ty = option_inner(ty).unwrap_or(ty);
+                return quote! {
+                    <#ty as _struct2swagger::JsonSchemaDefinition>::flattened_query_definitions(
+                        generator,
+                    )
+                    .into_iter()
+                    .map(|mut parameter| {
+                        parameter.required = parameter.required.map(|r| r && #required);
+                        parameter
+                    })
+                };
+            }
 
-                proc_macro2::TokenStream::from_iter(boxed.into_iter())
+            let name = field.attrs.name.deserialize_name();
+            let description = match field_description(field) {
+                Some(description) => quote! {
+                    ::std::option::Option::Some(::std::string::String::from(#description))
+                },
+                None => quote! { ::std::option::Option::None },
+            };
+            let deprecated = match field.attrs.schema.deprecated {
+                Some(true) => quote! { ::std::option::Option::Some(true) },
+                _ => quote! { ::std::option::Option::None },
+            };
+            let schema = constrained_schema(
+                field_type_schema(field),
+                &field.attrs.schema,
+                &constraint_keywords(&field.attrs.schema),
+            );
+            let schema = quote! {
+                _struct2swagger::swagger_object::SchemaObjectOrReferenceObject::SchemaObject(
+                    ::std::boxed::Box::new(#schema),
+                )
             };
+            quote! {
+                ::std::iter::once(_struct2swagger::ParameterObject {
+                    name: ::std::string::String::from(#name),
+                    where_in: _struct2swagger::ParameterIn::Query,
+                    description: #description,
+                    required: ::std::option::Option::Some(#required),
+                    deprecated: #deprecated,
+                    allow_empty_value: ::std::option::Option::None,
+                    schema: ::std::option::Option::Some(#schema),
+                })
+            }
+        });
 
-            schema_or_ref.push(TokenTree::Group(Group::new(Delimiter::Parenthesis, boxed)));
+    quote! {
+        ::std::iter::empty()
+            #(.chain(#query))*
+            .collect()
+    }
+}
 
-            proc_macro2::TokenStream::from_iter(schema_or_ref.into_iter())
-        };
+/// The schema of what serde writes for the fields of a variant, if anything.
+fn variant_content_schema(
+    cx: &Ctxt,
+    variant: &Variant,
+    attrs: &attr::Variant,
+) -> Option<TokenStream> {
+    match &variant.fields {
+        Fields::Unit => None,
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+            let ty = &unnamed.unnamed[0].ty;
+            Some(quote! { generator.subschema_for::<#ty>() })
+        }
+        Fields::Unnamed(unnamed) => {
+            let tys: Vec<&Type> = unnamed.unnamed.iter().map(|field| &field.ty).collect();
+            Some(tuple_schema(&tys))
+        }
+        Fields::Named(named) => Some(object_schema(
+            &get_named_fields(cx, named, attrs.rename_all, false),
+            None,
+        )),
+    }
+}
 
-        query_field_description.push(TokenTree::Group(Group::new(
-            Delimiter::Parenthesis,
-            schema_or_ref,
-        )));
+fn get_enum_json_schema_definition(
+    cx: &Ctxt,
+    container: &attr::Container,
+    data_enum: &DataEnum,
+) -> TokenStream {
+    let variants: Vec<(attr::Variant, &Variant)> = data_enum
+        .variants
+        .iter()
+        .map(|variant| (attr::Variant::from_ast(cx, variant, container), variant))
+        .filter(|(attrs, _)| !attrs.skip_serializing)
+        .collect();
+
+    match &container.tag {
+        TagType::External => {
+            let unit_names: Vec<&str> = variants
+                .iter()
+                .filter(|(_, variant)| matches!(variant.fields, Fields::Unit))
+                .map(|(attrs, _)| attrs.name.serialize_name())
+                .collect();
+            let unit_schema = quote! {
+                _struct2swagger::__private::json!({
+                    "type": "string",
+                    "enum": [ #(#unit_names,)* ],
+                })
+            };
+            if unit_names.len() == variants.len() {
+                return unit_schema;
+            }
 
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+            let unit_schema = if unit_names.is_empty() {
+                None
+            } else {
+                Some(unit_schema)
+            };
+            let schemas =
+                unit_schema
+                    .into_iter()
+                    .chain(variants.iter().filter_map(|(attrs, variant)| {
+                        let name = attrs.name.serialize_name();
+                        variant_content_schema(cx, variant, attrs).map(|content| {
+                            let schema = quote! {
+                                _struct2swagger::__private::json!({
+                                    "type": "object",
+                                    "required": [#name],
+                                    "properties": { #name: #content },
+                                    "additionalProperties": false,
+                                })
+                            };
+                            with_doc(schema, attrs.doc.as_deref())
+                        })
+                    }));
+
+            quote! {
+                _struct2swagger::__private::json!({
+                    "oneOf": [ #(#schemas,)* ],
+                })
+            }
+        }
+        TagType::Internal { tag } => {
+            let names = variants
+                .iter()
+                .map(|(attrs, _)| attrs.name.serialize_name());
+            let schemas = variants.iter().map(|(attrs, variant)| {
+                let tag = Some((tag.as_str(), attrs.name.serialize_name()));
+                let schema = match &variant.fields {
+                    Fields::Named(named) => {
+                        object_schema(&get_named_fields(cx, named, attrs.rename_all, false), tag)
+                    }
+                    Fields::Unit => object_schema(&[], tag),
+                    Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                        let tag_schema = object_schema(&[], tag);
+                        let content = variant_content_schema(cx, variant, attrs);
+                        quote! {
+                            _struct2swagger::__private::json!({
+                                "allOf": [#tag_schema, #content],
+                            })
+                        }
+                    }
+                    Fields::Unnamed(_) => {
+                        cx.error_spanned_by(
+                            variant,
+                            "#[serde(tag = \"...\")] cannot be used with tuple variants, \
+                             use a struct variant or add #[serde(content = \"...\")]",
+                        );
+                        TokenStream::new()
+                    }
+                };
+                with_doc(schema, attrs.doc.as_deref())
+            });
+
+            quote! {
+                ({
+                    let variants = ::std::vec![ #((#names, #schemas),)* ];
+                    generator.internally_tagged_schema::<Self>(#tag, variants)
+                })
+            }
+        }
+        TagType::Adjacent { tag, content } => {
+            let schemas = variants.iter().map(|(attrs, variant)| {
+                let name = attrs.name.serialize_name();
+                let schema = match variant_content_schema(cx, variant, attrs) {
+                    Some(content_schema) => quote! {
+                        _struct2swagger::__private::json!({
+                            "type": "object",
+                            "required": [#tag, #content],
+                            "properties": {
+                                #tag: { "type": "string", "enum": [#name] },
+                                #content: #content_schema,
+                            },
+                        })
+                    },
+                    None => object_schema(&[], Some((tag.as_str(), name))),
+                };
+                with_doc(schema, attrs.doc.as_deref())
+            });
+            quote! {
+                _struct2swagger::__private::json!({
+                    "oneOf": [ #(#schemas,)* ],
+                })
+            }
+        }
+        TagType::None => {
+            let schemas = variants.iter().map(|(attrs, variant)| {
+                let schema = variant_content_schema(cx, variant, attrs)
+                    .unwrap_or_else(|| quote! { generator.subschema_for::<()>() });
+                with_doc(schema, attrs.doc.as_deref())
+            });
+
+            quote! {
+                _struct2swagger::__private::json!({
+                    "anyOf": [ #(#schemas,)* ],
+                })
+            }
+        }
+    }
+}
 
-        let query_field_description =
-            proc_macro2::TokenStream::from_iter(query_field_description.into_iter());
+/// The body of `schema_name`. Each instance of a generic type is a schema of
+/// its own, named after its parameters: `Page<User>` is `Page_User`. A
+/// parameter without a name goes by its type name, `Page<u8>` is `Page_u8`.
+fn schema_name(ast: &DeriveInput, container: &attr::Container) -> TokenStream {
+    let name = container
+        .rename
+        .clone()
+        .unwrap_or_else(|| ast.ident.to_string().trim_start_matches("r#").to_owned());
+
+    let params: Vec<TokenStream> = ast
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                Some(quote! {
+                    name.push('_');
+                    name.push_str(
+                        &<#ident as _struct2swagger::JsonSchemaDefinition>::schema_name()
+                            .unwrap_or_else(_struct2swagger::__private::fallback_name::<#ident>),
+                    );
+                })
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                Some(quote! {
+                    name.push('_');
+                    name.push_str(&::std::string::ToString::to_string(&#ident));
+                })
+            }
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect();
 
-        query.push(TokenTree::Group(Group::new(
-            Delimiter::Brace,
-            query_field_description,
-        )));
-        query.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+    if params.is_empty() {
+        return quote! {
+            ::std::option::Option::Some(::std::string::String::from(#name))
+        };
+    }
+    quote! {
+        let mut name = ::std::string::String::from(#name);
+        #(#params)*
+        ::std::option::Option::Some(name)
     }
-
-    proc_macro2::TokenStream::from_iter(query.into_iter())
 }
 
 pub fn implements_swagger_trait(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
-    let ast = syn::parse2(input).unwrap();
-    let struct_name = get_struct_name(&ast);
-    let fields = get_fields(&ast);
+    let ast: DeriveInput = match syn::parse2(input) {
+        Ok(ast) => ast,
+        Err(err) => return err.to_compile_error(),
+    };
+    let ctxt = Ctxt::new();
+    let cx = &ctxt;
+    let container = attr::Container::from_ast(cx, &ast.attrs);
+
+    let (tokens, query_params) = match &ast.data {
+        Data::Enum(data_enum) => (
+            get_enum_json_schema_definition(cx, &container, data_enum),
+            // A bare enum has no name to give a query parameter: it only
+            // filters a query as the field of a struct, see `QueryDefinition`.
+            quote! { ::std::vec::Vec::new() },
+        ),
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(named_fields) => {
+                let fields =
+                    get_named_fields(cx, named_fields, container.rename_all, container.default);
+                (
+                    get_json_schema_definition(&fields),
+                    get_query_definitions(&fields),
+                )
+            }
+            // A newtype is serialized as the value it wraps
+            Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                let ty = &unnamed.unnamed[0].ty;
+                (
+                    quote! { generator.subschema_for::<#ty>() },
+                    quote! {
+                        <#ty as _struct2swagger::JsonSchemaDefinition>::flattened_query_definitions(
+                            generator,
+                        )
+                    },
+                )
+            }
+            Fields::Unnamed(unnamed) => {
+                let tys: Vec<&Type> = unnamed.unnamed.iter().map(|field| &field.ty).collect();
+                (tuple_schema(&tys), quote! { ::std::vec::Vec::new() })
+            }
+            Fields::Unit => (
+                quote! { generator.subschema_for::<()>() },
+                quote! { ::std::vec::Vec::new() },
+            ),
+        },
+        Data::Union(data_union) => {
+            cx.error_spanned_by(
+                data_union.union_token,
+                "unions are not supported by #[derive(Swagger)], \
+                 implement JsonSchemaDefinition for it by hand instead",
+            );
+            (TokenStream::new(), TokenStream::new())
+        }
+    };
 
-    let (required_properties, tokens) = get_json_schema_definition(&fields);
+    if let Err(errors) = ctxt.check() {
+        return errors;
+    }
 
-    let query_params = get_query_definitions(&fields);
+    let tokens = with_doc(tokens, container.doc.as_deref());
 
-    let struct_name_ident = TokenTree::Ident(Ident::new(&struct_name, Span::call_site()));
+    let struct_name_ident = &ast.ident;
 
-    let query_definition_quote = quote! {
-        impl struct2swagger::QueryDefinition for #struct_name_ident {
-            fn get_query_definitions() -> Vec<struct2swagger::ParameterObject> {
-                vec![
-                    #query_params
-                ]
-            }
-        }
+    let mut generics = ast.generics.clone();
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(parse_quote!(_struct2swagger::JsonSchemaDefinition));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let schema_name = schema_name(&ast, &container);
+
+    // The generated code names the crate `_struct2swagger`, so that it compiles
+    // whatever the caller imported and under whichever path the crate lives.
+    let use_struct2swagger = match &container.krate {
+        Some(path) => quote! {
+            use #path as _struct2swagger;
+        },
+        None => quote! {
+            #[allow(unused_extern_crates, clippy::useless_attribute)]
+            extern crate struct2swagger as _struct2swagger;
+        },
     };
-    let json_schema_definition_quote;
-    if required_properties.is_empty() {
-        json_schema_definition_quote = quote! {
-            impl struct2swagger::JsonSchemaDefinition for #struct_name_ident {
-                fn get_json_schema_definition() -> serde_json::Value {
-                    json!({
-                        "type": "object",
-                        "properties": #tokens,
-                    })
+
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
+        const _: () = {
+            #use_struct2swagger
+
+            impl #impl_generics _struct2swagger::JsonSchemaDefinition for #struct_name_ident #ty_generics #where_clause {
+                fn json_schema(
+                    generator: &mut _struct2swagger::SchemaGenerator,
+                ) -> _struct2swagger::__private::serde_json::Value {
+                    generator.define::<Self>(|generator| #tokens)
+                }
+
+                fn schema_name() -> ::std::option::Option<::std::string::String> {
+                    #schema_name
+                }
+
+                fn flattened_query_definitions(
+                    generator: &mut _struct2swagger::SchemaGenerator,
+                ) -> ::std::vec::Vec<_struct2swagger::ParameterObject> {
+                    <Self as _struct2swagger::QueryDefinition>::query_definitions(generator)
                 }
             }
-        }
-    } else {
-        json_schema_definition_quote = quote! {
-            impl struct2swagger::JsonSchemaDefinition for #struct_name_ident {
-                fn get_json_schema_definition() -> serde_json::Value {
-                    json!({
-                        "type": "object",
-                        "required": [ #required_properties ],
-                        "properties": #tokens,
-                    })
+            impl #impl_generics _struct2swagger::QueryDefinition for #struct_name_ident #ty_generics #where_clause {
+                fn query_definitions(
+                    generator: &mut _struct2swagger::SchemaGenerator,
+                ) -> ::std::vec::Vec<_struct2swagger::ParameterObject> {
+                    #query_params
                 }
             }
-        }
+        };
     }
+}
 
-    quote! {
-        #json_schema_definition_quote
-        #query_definition_quote
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_option_only_at_top_level() {
+        let optional: Vec<Type> = vec![
+            parse_quote!(Option<u8>),
+            parse_quote!(std::option::Option<u8>),
+            parse_quote!(::core::option::Option<Vec<u8>>),
+        ];
+        for ty in optional {
+            assert!(is_option(&ty));
+        }
+
+        let required: Vec<Type> = vec![
+            parse_quote!(Vec<Option<u8>>),
+            parse_quote!(HashMap<String, Option<u8>>),
+            parse_quote!(my::Option<u8>),
+            parse_quote!(Option),
+            parse_quote!(<T as Trait>::Option<u8>),
+        ];
+        for ty in required {
+            assert!(!is_option(&ty));
+        }
     }
 }
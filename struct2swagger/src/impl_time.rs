@@ -0,0 +1,196 @@
+//! The schemas of the `time` types.
+//!
+//! serde writes them as tuples of their parts, or as strings when time's
+//! `serde-human-readable` feature is on. Features are unified across the
+//! whole build, so whether it is on can't be told from here: each schema
+//! follows what serde writes for a sample value.
+//!
+//! Only the strings of `Date` are in a format of RFC 3339: the others get a
+//! `pattern`.
+
+use serde::Serialize;
+use serde_json::Value;
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time};
+
+use crate::{JsonSchemaDefinition, SchemaGenerator};
+
+const OFFSET_DATE_TIME_PATTERN: &str =
+    r"^[+-]?\d{4,6}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}\.\d+ [+-]\d{2}:\d{2}:\d{2}$";
+const PRIMITIVE_DATE_TIME_PATTERN: &str = r"^[+-]?\d{4,6}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}\.\d+$";
+const TIME_PATTERN: &str = r"^\d{2}:\d{2}:\d{2}\.\d+$";
+// Seconds, with nine decimals
+const DURATION_PATTERN: &str = r"^-?\d+\.\d{9}$";
+
+/// The schema of a `time` type, `sample` being what serde writes for one:
+/// `human_readable` for a string, or else the schema of `Parts`, the tuple
+/// serde writes otherwise.
+fn time_schema<Parts: JsonSchemaDefinition>(
+    generator: &mut SchemaGenerator,
+    sample: Option<Value>,
+    human_readable: Value,
+) -> Value {
+    match sample {
+        Some(Value::String(_)) => human_readable,
+        _ => Parts::json_schema(generator),
+    }
+}
+
+fn sample<T: Serialize>(value: T) -> Option<Value> {
+    serde_json::to_value(value).ok()
+}
+
+impl JsonSchemaDefinition for OffsetDateTime {
+    // Year, ordinal, hour, minute, second, nanosecond, and the hours, minutes
+    // and seconds of the offset
+    fn json_schema(generator: &mut SchemaGenerator) -> Value {
+        time_schema::<(i32, u16, u8, u8, u8, u32, i8, i8, i8)>(
+            generator,
+            sample(OffsetDateTime::UNIX_EPOCH),
+            json!({
+                "type": "string",
+                "pattern": OFFSET_DATE_TIME_PATTERN,
+            }),
+        )
+    }
+}
+
+impl JsonSchemaDefinition for PrimitiveDateTime {
+    // Year, ordinal, hour, minute, second and nanosecond
+    fn json_schema(generator: &mut SchemaGenerator) -> Value {
+        time_schema::<(i32, u16, u8, u8, u8, u32)>(
+            generator,
+            sample(PrimitiveDateTime::MIN),
+            json!({
+                "type": "string",
+                "pattern": PRIMITIVE_DATE_TIME_PATTERN,
+            }),
+        )
+    }
+}
+
+impl JsonSchemaDefinition for Date {
+    // Year and ordinal
+    fn json_schema(generator: &mut SchemaGenerator) -> Value {
+        time_schema::<(i32, u16)>(
+            generator,
+            sample(Date::MIN),
+            json!({
+                "type": "string",
+                "format": "date",
+            }),
+        )
+    }
+}
+
+impl JsonSchemaDefinition for Time {
+    // Hour, minute, second and nanosecond
+    fn json_schema(generator: &mut SchemaGenerator) -> Value {
+        time_schema::<(u8, u8, u8, u32)>(
+            generator,
+            sample(Time::MIDNIGHT),
+            json!({
+                "type": "string",
+                "pattern": TIME_PATTERN,
+            }),
+        )
+    }
+}
+
+impl JsonSchemaDefinition for Duration {
+    // Whole seconds and nanoseconds
+    fn json_schema(generator: &mut SchemaGenerator) -> Value {
+        time_schema::<(i64, i32)>(
+            generator,
+            sample(Duration::ZERO),
+            json!({
+                "type": "string",
+                "pattern": DURATION_PATTERN,
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::assert_matches_pattern;
+    use time::{Month, UtcOffset};
+
+    fn sample() -> PrimitiveDateTime {
+        let date = Date::from_calendar_date(2020, Month::January, 2).unwrap();
+        let time = Time::from_hms_milli(3, 4, 5, 123).unwrap();
+        PrimitiveDateTime::new(date, time)
+    }
+
+    #[test]
+    fn date_time() {
+        let offset = UtcOffset::from_hms(1, 0, 0).unwrap();
+
+        assert_eq!(
+            OffsetDateTime::get_json_schema_definition(),
+            <(i32, u16, u8, u8, u8, u32, i8, i8, i8)>::get_json_schema_definition()
+        );
+        assert_eq!(
+            serde_json::to_value(sample().assume_offset(offset)).unwrap(),
+            json!([2020, 2, 3, 4, 5, 123_000_000, 1, 0, 0])
+        );
+        assert_eq!(
+            PrimitiveDateTime::get_json_schema_definition(),
+            <(i32, u16, u8, u8, u8, u32)>::get_json_schema_definition()
+        );
+        assert_eq!(
+            serde_json::to_value(sample()).unwrap(),
+            json!([2020, 2, 3, 4, 5, 123_000_000])
+        );
+    }
+
+    #[test]
+    fn date_and_time() {
+        assert_eq!(
+            Date::get_json_schema_definition(),
+            <(i32, u16)>::get_json_schema_definition()
+        );
+        assert_eq!(
+            serde_json::to_value(sample().date()).unwrap(),
+            json!([2020, 2])
+        );
+        assert_eq!(
+            Time::get_json_schema_definition(),
+            <(u8, u8, u8, u32)>::get_json_schema_definition()
+        );
+        assert_eq!(
+            serde_json::to_value(Time::from_hms(3, 4, 5).unwrap()).unwrap(),
+            json!([3, 4, 5, 0])
+        );
+        assert_eq!(
+            Duration::get_json_schema_definition(),
+            <(i64, i32)>::get_json_schema_definition()
+        );
+        assert_eq!(
+            serde_json::to_value(Duration::milliseconds(-1500)).unwrap(),
+            json!([-1, -500_000_000])
+        );
+    }
+
+    #[test]
+    fn human_readable() {
+        let mut generator = SchemaGenerator::default();
+        let human_readable = |sample: &str, pattern: &str| {
+            let schema = json!({ "type": "string", "pattern": pattern });
+            assert_matches_pattern(&schema, &json!(sample));
+            schema
+        };
+
+        let schema = human_readable(
+            "2020-01-02 03:04:05.123 +01:00:00",
+            OFFSET_DATE_TIME_PATTERN,
+        );
+        assert_eq!(
+            time_schema::<(i32, u16)>(&mut generator, Some(json!("")), schema.clone()),
+            schema
+        );
+        human_readable("2020-01-02 03:04:05.123", PRIMITIVE_DATE_TIME_PATTERN);
+        human_readable("03:04:05.0", TIME_PATTERN);
+        human_readable("-1.500000000", DURATION_PATTERN);
+    }
+}
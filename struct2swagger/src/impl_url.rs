@@ -0,0 +1,33 @@
+//! The schema of `url::Url`, written as a string by url's `serde` feature.
+
+use url::Url;
+
+use crate::{JsonSchemaDefinition, SchemaGenerator};
+
+impl JsonSchemaDefinition for Url {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "format": "uri",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url() {
+        let url = Url::parse("https://example.com/path?query#fragment").unwrap();
+
+        assert_eq!(
+            Url::get_json_schema_definition(),
+            json!({ "type": "string", "format": "uri" })
+        );
+        assert_eq!(
+            serde_json::to_value(url).unwrap(),
+            "https://example.com/path?query#fragment"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! The schema of `uuid::Uuid`, written hyphenated by uuid's `serde` feature.
+
+use uuid::Uuid;
+
+use crate::{JsonSchemaDefinition, SchemaGenerator};
+
+impl JsonSchemaDefinition for Uuid {
+    fn json_schema(_: &mut SchemaGenerator) -> serde_json::Value {
+        json!({
+            "type": "string",
+            "format": "uuid",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uuid() {
+        let uuid = Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+
+        assert_eq!(
+            Uuid::get_json_schema_definition(),
+            json!({ "type": "string", "format": "uuid" })
+        );
+        assert_eq!(
+            serde_json::to_value(uuid).unwrap(),
+            "67e55044-10b1-426f-9247-bb680e5fe0c8"
+        );
+    }
+}
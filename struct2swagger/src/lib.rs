@@ -11,90 +11,175 @@ extern crate serde_derive;
 #[macro_use]
 extern crate serde_json;
 
+mod attr;
+mod case;
+mod ctxt;
+mod fallback;
+pub mod generator;
+#[cfg(feature = "bigdecimal")]
+mod impl_bigdecimal;
+#[cfg(feature = "chrono")]
+mod impl_chrono;
 mod impl_data_types;
+#[cfg(feature = "mime")]
+mod impl_mime;
+#[cfg(feature = "num-bigint")]
+mod impl_num_bigint;
+#[cfg(feature = "rust_decimal")]
+mod impl_rust_decimal;
+#[cfg(feature = "semver")]
+mod impl_semver;
 mod impl_swagger_trait;
+#[cfg(feature = "time")]
+mod impl_time;
+#[cfg(feature = "url")]
+mod impl_url;
+#[cfg(feature = "uuid")]
+mod impl_uuid;
+pub mod schema;
 pub mod swagger_object;
 
-#[derive(Debug)]
 struct Field {
-    name: String,
-    ty: Vec<proc_macro2::TokenTree>,
+    attrs: attr::Field,
+    ty: syn::Type,
 }
 
+pub use generator::{SchemaGenerator, SchemaSettings};
 pub use impl_swagger_trait::implements_swagger_trait;
+
+/// Used by the derived implementations, not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub extern crate serde_json;
+    pub use self::serde_json::json;
+    pub use crate::generator::fallback_name;
+}
 pub use swagger_object::{ParameterIn, ParameterObject};
 
+const SCHEMA_METHODS: &str = "json_schema or get_json_schema_definition";
+const QUERY_METHODS: &str = "query_definitions or get_query_definitions";
+
+/// Implementations provide either `json_schema` or, like the older ones,
+/// `get_json_schema_definition`: each method defaults to the other, and
+/// calling either panics when neither is implemented.
 pub trait JsonSchemaDefinition {
-    fn get_json_schema_definition() -> serde_json::Value;
+    /// The schema of the type, generated with `generator`.
+    fn json_schema(_generator: &mut SchemaGenerator) -> serde_json::Value {
+        fallback::guard::<Self, _>(SCHEMA_METHODS, Self::get_json_schema_definition)
+    }
+
+    /// The schema of the type, generated with the default settings.
+    ///
+    /// A recursive type refers to itself with a `$ref` to
+    /// `components/schemas`, which this schema alone does not have: add it to
+    /// a document with `SwaggerObject::schema_for`, or take its component
+    /// schemas from the `SchemaGenerator` given to `root_schema_for`.
+    fn get_json_schema_definition() -> serde_json::Value {
+        fallback::guard::<Self, _>(SCHEMA_METHODS, || {
+            SchemaGenerator::default().root_schema_for::<Self>()
+        })
+    }
+
+    /// The name of the schema in `components/schemas`, for the types worth
+    /// describing once and referencing with `$ref`.
+    /// Types without a name, like primitives and collections, are always inlined.
+    fn schema_name() -> Option<String> {
+        None
+    }
+
+    /// The query parameters made by the fields of the type when it is
+    /// flattened into, or wrapped as, a query string: those of its
+    /// `QueryDefinition` for the derived types. Other types have none.
+    fn flattened_query_definitions(_generator: &mut SchemaGenerator) -> Vec<ParameterObject> {
+        Vec::new()
+    }
 }
+
+/// The query parameters a type deserializes from.
+///
+/// Derived for structs, whose named fields make the parameters, and newtypes
+/// over them. The derived implementation of an enum has no parameters, since
+/// a query string has no name to give it: an enum filters a query as the
+/// field of such a struct, like `status: Option<Status>`.
+///
+/// Like `JsonSchemaDefinition`, implementations provide either method.
 pub trait QueryDefinition {
-    fn get_query_definitions() -> Vec<ParameterObject>;
+    /// The query parameters of the type, with their schemas generated with `generator`.
+    fn query_definitions(_generator: &mut SchemaGenerator) -> Vec<ParameterObject> {
+        fallback::guard::<Self, _>(QUERY_METHODS, Self::get_query_definitions)
+    }
+
+    /// The query parameters of the type, with their schemas generated with the default settings.
+    fn get_query_definitions() -> Vec<ParameterObject> {
+        fallback::guard::<Self, _>(QUERY_METHODS, || {
+            Self::query_definitions(&mut SchemaGenerator::default())
+        })
+    }
 }
 
 #[macro_export]
 macro_rules! swagger_add_router {
     ($swagger_object:expr, "GET", $path:literal, $query_params: ident, 200, $description: expr, $response:ident) => {{
-        $swagger_object.add_route(
+        let swagger_object = &mut $swagger_object;
+        let parameters = swagger_object
+            .query_parameters_for::<$query_params>()
+            .into_iter()
+            .map(|p| {
+                $crate::swagger_object::ParameterObjectOrReferenceObject::ParameterObject(Box::new(
+                    p,
+                ))
+            })
+            .collect();
+        let response = swagger_object.schema_for::<$response>();
+        swagger_object.add_route(
             "GET",
             String::from($path),
-            Some(
-                <$query_params>::get_query_definitions()
-                    .into_iter()
-                    .map(|p| {
-                        $crate::swagger_object::ParameterObjectOrReferenceObject::ParameterObject(
-                            Box::new(p),
-                        )
-                    })
-                    .collect(),
-            ),
+            Some(parameters),
             None,
-            vec![(
-                200 as u16,
-                ($description, $response::get_json_schema_definition()),
-            )],
+            vec![(200 as u16, ($description, response))],
         )
     }};
     ($swagger_object:expr, "GET", $path:literal, 200, $description: expr, $response:ident) => {{
-        $swagger_object.add_route(
+        let swagger_object = &mut $swagger_object;
+        let response = swagger_object.schema_for::<$response>();
+        swagger_object.add_route(
             "GET",
             String::from($path),
             None,
             None,
-            vec![(
-                200 as u16,
-                ($description, $response::get_json_schema_definition()),
-            )],
+            vec![(200 as u16, ($description, response))],
         )
     }};
     ($swagger_object:expr, "DELETE", $path:literal, 200, $description: expr, $response:ident) => {{
-        $swagger_object.add_route(
+        let swagger_object = &mut $swagger_object;
+        let response = swagger_object.schema_for::<$response>();
+        swagger_object.add_route(
             "DELETE",
             String::from($path),
             None,
             None,
-            vec![(
-                200 as u16,
-                ($description, $response::get_json_schema_definition()),
-            )],
+            vec![(200 as u16, ($description, response))],
         )
     }};
     ($swagger_object:expr, $method:literal, $path:literal, "request_body", $req: ident, 200, $description: expr, $response:ident) => {{
-        use struct2swagger::swagger_object::{
+        use $crate::swagger_object::{
             MediaTypeObject, RequestBodyObject, SchemaObjectOrReferenceObject,
         };
-        let mut content_hash_map = HashMap::new();
+        let swagger_object = &mut $swagger_object;
+        let mut content_hash_map = ::std::collections::HashMap::new();
         content_hash_map.insert(
             "application/json".to_owned(),
             MediaTypeObject {
                 schema: Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(
-                    $req::get_json_schema_definition(),
+                    swagger_object.schema_for::<$req>(),
                 ))),
                 example: None,
                 examples: None,
                 encoding: None,
             },
         );
-        $swagger_object.add_route(
+        let response = swagger_object.schema_for::<$response>();
+        swagger_object.add_route(
             $method,
             String::from($path),
             None,
@@ -103,10 +188,7 @@ macro_rules! swagger_add_router {
                 content: content_hash_map,
                 required: Some(true),
             }),
-            vec![(
-                200 as u16,
-                ($description, $response::get_json_schema_definition()),
-            )],
+            vec![(200 as u16, ($description, response))],
         )
     }};
 }
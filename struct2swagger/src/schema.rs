@@ -0,0 +1,179 @@
+//! Helpers the derived implementations use to compose schemas at runtime.
+
+use serde_json::Value;
+
+/// The keywords an object schema can carry and still be merged into another one.
+const MERGEABLE_KEYWORDS: &[&str] = &["type", "properties", "required", "title", "description"];
+
+/// Adds the fields of a `#[serde(flatten)]` field, described by `inner`, to the
+/// object schema `parent`.
+///
+/// Plain objects have their properties merged into `parent`: their required
+/// properties stay required unless the field is an `Option`. Anything else,
+/// like a `$ref` or a map, cannot be merged and is appended to `allOf`.
+pub fn flatten_schema(parent: &mut Value, inner: Value, optional: bool) {
+    let mergeable = inner.get("type") == Some(&json!("object"))
+        && inner.get("properties").is_some_and(Value::is_object)
+        && inner
+            .as_object()
+            .is_some_and(|o| o.keys().all(|k| MERGEABLE_KEYWORDS.contains(&&**k)));
+
+    if !mergeable {
+        let all_of = parent
+            .as_object_mut()
+            .unwrap()
+            .entry("allOf")
+            .or_insert_with(|| json!([]));
+        all_of.as_array_mut().unwrap().push(inner);
+        return;
+    }
+
+    if let Some(Value::Object(properties)) = inner.get("properties") {
+        let parent_properties = parent
+            .as_object_mut()
+            .unwrap()
+            .entry("properties")
+            .or_insert_with(|| json!({}));
+        for (name, property) in properties {
+            parent_properties
+                .as_object_mut()
+                .unwrap()
+                .insert(name.clone(), property.clone());
+        }
+    }
+
+    if optional {
+        return;
+    }
+    if let Some(Value::Array(required)) = inner.get("required") {
+        if required.is_empty() {
+            return;
+        }
+        let parent_required = parent
+            .as_object_mut()
+            .unwrap()
+            .entry("required")
+            .or_insert_with(|| json!([]));
+        parent_required
+            .as_array_mut()
+            .unwrap()
+            .extend(required.iter().cloned());
+    }
+}
+
+/// Adds the keywords of `extension` to `schema`.
+///
+/// A `$ref` ignores its sibling keywords, so it is wrapped in an `allOf` first.
+pub fn extend_schema(schema: Value, extension: Value) -> Value {
+    let mut schema = if schema.get("$ref").is_some() {
+        json!({ "allOf": [schema] })
+    } else {
+        schema
+    };
+
+    if let (Some(schema), Value::Object(extension)) = (schema.as_object_mut(), extension) {
+        schema.extend(extension);
+    }
+    schema
+}
+
+/// Panics unless `value`, what serde wrote for a sample value, is a string
+/// matching the `pattern` of `schema`.
+#[cfg(test)]
+pub(crate) fn assert_matches_pattern(schema: &Value, value: &Value) {
+    let pattern = schema["pattern"].as_str().expect("a pattern");
+    let value = value.as_str().expect("a string");
+    let regex = regex::Regex::new(pattern).unwrap();
+    assert!(
+        regex.is_match(value),
+        "{:?} does not match {:?}",
+        value,
+        pattern
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_schema_merges_objects() {
+        let mut parent = json!({
+            "type": "object",
+            "required": ["a"],
+            "properties": { "a": { "type": "string" } },
+        });
+        flatten_schema(
+            &mut parent,
+            json!({
+                "type": "object",
+                "required": ["b"],
+                "properties": { "b": { "type": "string" }, "c": { "type": "string" } },
+            }),
+            false,
+        );
+
+        assert_eq!(
+            parent,
+            json!({
+                "type": "object",
+                "required": ["a", "b"],
+                "properties": {
+                    "a": { "type": "string" },
+                    "b": { "type": "string" },
+                    "c": { "type": "string" },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn flatten_schema_optional_is_not_required() {
+        let mut parent = json!({ "type": "object", "properties": {} });
+        flatten_schema(
+            &mut parent,
+            json!({
+                "type": "object",
+                "required": ["b"],
+                "properties": { "b": { "type": "string" } },
+            }),
+            true,
+        );
+
+        assert_eq!(
+            parent,
+            json!({ "type": "object", "properties": { "b": { "type": "string" } } })
+        );
+    }
+
+    #[test]
+    fn flatten_schema_falls_back_to_all_of() {
+        let mut parent = json!({ "type": "object", "properties": {} });
+        let map = json!({ "type": "object", "additionalProperties": { "type": "string" } });
+        flatten_schema(&mut parent, map.clone(), false);
+
+        assert_eq!(
+            parent,
+            json!({ "type": "object", "properties": {}, "allOf": [map] })
+        );
+    }
+
+    #[test]
+    fn extend_schema_adds_keywords() {
+        assert_eq!(
+            extend_schema(json!({ "type": "string" }), json!({ "readOnly": true })),
+            json!({ "type": "string", "readOnly": true })
+        );
+    }
+
+    #[test]
+    fn extend_schema_wraps_references() {
+        assert_eq!(
+            extend_schema(
+                json!({ "$ref": "#/components/schemas/User" }),
+                json!({ "readOnly": true })
+            ),
+            json!({ "allOf": [{ "$ref": "#/components/schemas/User" }], "readOnly": true })
+        );
+    }
+}
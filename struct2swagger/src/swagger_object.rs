@@ -2,9 +2,13 @@ use std::collections::HashMap;
 
 use serde::{Serialize, Serializer};
 
-#[derive(Debug, Clone)]
+use crate::generator::{SchemaGenerator, SchemaSettings};
+use crate::{JsonSchemaDefinition, QueryDefinition};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SwaggerVersion {
     V300,
+    V310,
 }
 impl Serialize for SwaggerVersion {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -13,6 +17,7 @@ impl Serialize for SwaggerVersion {
     {
         serializer.serialize_str(match *self {
             SwaggerVersion::V300 => "3.0.0",
+            SwaggerVersion::V310 => "3.1.0",
         })
     }
 }
@@ -97,7 +102,7 @@ pub enum AnyOrExpression {
     Expression(String),
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ComponentsObject {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -262,18 +267,13 @@ impl Serialize for ResponsesObject {
         S: Serializer,
     {
         let mut r: HashMap<String, ResponseObjectOrReferenceObject> = HashMap::new();
-        if self.responses_per_http_status_codes.is_some() {
-            for (k, value) in self
-                .responses_per_http_status_codes
-                .as_ref()
-                .unwrap()
-                .iter()
-            {
-                r.insert(k.to_string().to_owned(), value.clone());
+        if let Some(responses_per_http_status_codes) = &self.responses_per_http_status_codes {
+            for (k, value) in responses_per_http_status_codes.iter() {
+                r.insert(k.to_string(), value.clone());
             }
         }
-        if self.default.is_some() {
-            r.insert("default".to_owned(), self.default.as_ref().unwrap().clone());
+        if let Some(default) = &self.default {
+            r.insert("default".to_owned(), default.clone());
         }
 
         r.serialize(serializer)
@@ -431,12 +431,20 @@ pub struct SwaggerObject {
     pub tags: Option<Vec<TagObject>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub external_docs: Option<ExternalDocumentationObject>,
+    /// Generates the schemas of the operations, collecting their components.
+    #[serde(skip)]
+    pub schema_generator: SchemaGenerator,
 }
 
 impl SwaggerObject {
     pub fn new(title: &str, version: &str) -> Self {
+        Self::with_settings(title, version, SchemaSettings::default())
+    }
+
+    /// A document for the OpenAPI version of `settings`, with schemas generated following them.
+    pub fn with_settings(title: &str, version: &str, settings: SchemaSettings) -> Self {
         Self {
-            openapi: SwaggerVersion::V300,
+            openapi: settings.version,
             info: InfoObject {
                 title: title.to_owned(),
                 version: version.to_owned(),
@@ -451,11 +459,47 @@ impl SwaggerObject {
             security: None,
             tags: None,
             external_docs: None,
+            schema_generator: SchemaGenerator::new(settings),
+        }
+    }
+
+    /// The schema of `T`, to use in an operation of this document.
+    pub fn schema_for<T: JsonSchemaDefinition + ?Sized>(&mut self) -> serde_json::Value {
+        let schema = self.schema_generator.subschema_for::<T>();
+        self.add_component_schemas();
+        schema
+    }
+
+    /// The query parameters of `T`, to use in an operation of this document.
+    pub fn query_parameters_for<T: QueryDefinition + ?Sized>(&mut self) -> Vec<ParameterObject> {
+        let parameters = T::query_definitions(&mut self.schema_generator);
+        self.add_component_schemas();
+        parameters
+    }
+
+    fn add_component_schemas(&mut self) {
+        let definitions = self.schema_generator.definitions();
+        if definitions.is_empty() {
+            return;
+        }
+
+        let components = self
+            .components
+            .get_or_insert_with(ComponentsObject::default)
+            .schemas
+            .get_or_insert_with(HashMap::new);
+        for (name, schema) in definitions {
+            if !components.contains_key(name) {
+                components.insert(
+                    name.clone(),
+                    SchemaObjectOrReferenceObject::SchemaObject(Box::new(schema.clone())),
+                );
+            }
         }
     }
 
     pub fn add_route(
-        self: &mut Self,
+        &mut self,
         method: &str,
         path: String,
         parameters: Option<Vec<ParameterObjectOrReferenceObject>>,
@@ -507,12 +551,8 @@ impl SwaggerObject {
             );
         }
 
-        let request_body = match request_body {
-            Some(rq) => Some(RequestBodyObjectOrReferenceObject::RequestBodyObject(
-                Box::new(rq),
-            )),
-            None => None,
-        };
+        let request_body = request_body
+            .map(|rq| RequestBodyObjectOrReferenceObject::RequestBodyObject(Box::new(rq)));
         let operation_object = OperationObject {
             responses: ResponsesObject {
                 default: None,
@@ -5,7 +5,7 @@ extern crate struct2swagger;
 
 use struct2swagger::implements_swagger_trait;
 
-#[proc_macro_derive(Swagger, attributes(swagger))]
+#[proc_macro_derive(Swagger, attributes(swagger, serde))]
 pub fn swagger_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     implements_swagger_trait(proc_macro2::TokenStream::from(input)).into()
 }
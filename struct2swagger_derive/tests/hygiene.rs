@@ -0,0 +1,65 @@
+//! The derived code must compile without any `#[macro_use]` or `use` from the caller,
+//! and whatever the caller names its own items.
+#![deny(warnings)]
+#![allow(dead_code)]
+
+use struct2swagger_derive::Swagger;
+
+struct Vec;
+struct Box;
+struct Option;
+struct String;
+mod serde_json {}
+mod std {}
+
+#[derive(Swagger)]
+struct Hygiene {
+    /// A documented field.
+    #[swagger(minimum = 1)]
+    count: u8,
+    name: ::std::option::Option<::std::string::String>,
+}
+
+#[derive(Swagger)]
+#[serde(tag = "type")]
+enum HygieneEnum {
+    First { value: u8 },
+    Second,
+}
+
+#[derive(Swagger)]
+enum HygieneUnitEnum {
+    First,
+    Second,
+}
+
+#[derive(Swagger)]
+struct HygieneTuple(u8, u16);
+
+mod platform {
+    pub mod swagger {
+        pub use struct2swagger::*;
+    }
+}
+
+#[derive(Swagger)]
+#[swagger(crate = "platform::swagger")]
+struct Reexported {
+    count: u8,
+}
+
+#[test]
+fn derives_without_imports() {
+    use struct2swagger::{JsonSchemaDefinition, QueryDefinition};
+
+    assert_eq!(
+        Hygiene::get_json_schema_definition()["properties"]["count"]["description"],
+        "A documented field."
+    );
+    assert_eq!(Hygiene::get_query_definitions().len(), 2);
+    assert!(HygieneEnum::get_json_schema_definition()["discriminator"].is_object());
+    assert_eq!(
+        Reexported::get_json_schema_definition()["required"],
+        ::serde_json::json!(["count"])
+    );
+}
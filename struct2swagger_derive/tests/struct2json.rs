@@ -3,17 +3,24 @@
 extern crate proc_macro;
 extern crate proc_macro2;
 #[macro_use]
+extern crate serde_derive;
+#[macro_use]
 extern crate serde_json;
 #[macro_use]
 extern crate quote;
-use proc_macro2::{Ident, Punct, Spacing, Span, TokenTree};
+use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::iter::FromIterator;
+use std::rc::Rc;
+use std::sync::Arc;
 
 extern crate struct2swagger;
 #[macro_use]
 extern crate struct2swagger_derive;
 
-use struct2swagger::JsonSchemaDefinition;
+use struct2swagger::{JsonSchemaDefinition, SchemaGenerator};
 
 #[test]
 fn test_1() {
@@ -32,15 +39,11 @@ fn test_1() {
 
 #[test]
 fn test_2() {
-    let mut tokens = Vec::new();
-
-    tokens.push(TokenTree::Punct(Punct::new('(', Spacing::Alone)));
-    tokens.push(TokenTree::Punct(Punct::new('{', Spacing::Alone)));
-
-    tokens.push(TokenTree::Punct(Punct::new('}', Spacing::Alone)));
-    tokens.push(TokenTree::Punct(Punct::new(')', Spacing::Alone)));
-
-    let tokens = proc_macro2::TokenStream::from_iter(tokens.into_iter());
+    let braces = TokenTree::Group(Group::new(Delimiter::Brace, TokenStream::new()));
+    let tokens = TokenStream::from_iter(vec![TokenTree::Group(Group::new(
+        Delimiter::Parenthesis,
+        TokenStream::from_iter(vec![braces]),
+    ))]);
     let q = quote! {
         impl JsonSchemaDefinition for MyStructName {
             fn get_json_schema_definition() -> serde_json::Value {
@@ -50,7 +53,7 @@ fn test_2() {
     };
     let s = q.to_string();
 
-    assert_eq!(s, "impl JsonSchemaDefinition for MyStructName { fn get_json_schema_definition () -> serde_json :: Value { json ! ( { } ) ; } }");
+    assert_eq!(s, "impl JsonSchemaDefinition for MyStructName { fn get_json_schema_definition () -> serde_json :: Value { json ! ({ }) ; } }");
 }
 
 #[test]
@@ -85,6 +88,7 @@ fn simple_struct() {
                     "maximum":255,
                     "minimum":0,
                     "type":"integer",
+                    "format":"int32",
                 },
                 "val2": {
                     "type":"string",
@@ -117,6 +121,7 @@ fn struct_with_arrays() {
                         "maximum":255,
                         "minimum":0,
                         "type":"integer",
+                        "format":"int32",
                     },
                 },
                 "val2": {
@@ -135,6 +140,7 @@ fn struct_with_arrays() {
                                 "maximum":255,
                                 "minimum":0,
                                 "type":"integer",
+                                "format":"int32",
                             },
                         },
                     },
@@ -165,11 +171,13 @@ fn struct_with_option() {
                     "maximum":255,
                     "minimum":0,
                     "type":"integer",
+                    "format":"int32",
                 },
                 "val2": {
                     "maximum":255,
                     "minimum":0,
                     "type":"integer",
+                    "format":"int32",
                 },
                 "val3": {
                     "type":"string",
@@ -181,7 +189,7 @@ fn struct_with_option() {
     );
 }
 
-#[derive(Swagger)]
+#[derive(Serialize, Swagger)]
 struct MySubStruct {
     val1: u8,
     val2: String,
@@ -206,6 +214,7 @@ fn struct_with_struct() {
                     "maximum":255,
                     "minimum":0,
                     "type":"integer",
+                    "format":"int32",
                 },
                 "val2": {
                     "type": "object",
@@ -214,6 +223,7 @@ fn struct_with_struct() {
                             "maximum":255,
                             "minimum":0,
                             "type":"integer",
+                            "format":"int32",
                         },
                         "val2": {
                             "type": "string",
@@ -230,6 +240,7 @@ fn struct_with_struct() {
                                 "maximum":255,
                                 "minimum":0,
                                 "type":"integer",
+                                "format":"int32",
                             },
                             "val2": {
                                 "type": "string",
@@ -263,6 +274,7 @@ fn struct_with_all_option() {
                     "maximum":255,
                     "minimum":0,
                     "type":"integer",
+                    "format":"int32",
                 },
                 "val2": {
                     "type": "string",
@@ -288,3 +300,1411 @@ fn empty_struct() {
         })
     );
 }
+
+#[derive(Serialize, Swagger)]
+enum Status {
+    Active,
+    Inactive,
+}
+
+#[test]
+fn fieldless_enum() {
+    let t = Status::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "type": "string",
+            "enum": ["Active", "Inactive"],
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(Status::Inactive).unwrap(),
+        json!("Inactive")
+    );
+}
+
+#[derive(Serialize, Swagger, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum Role {
+    SuperAdmin,
+    #[serde(rename = "guest")]
+    Anonymous,
+    Editor,
+}
+
+#[test]
+fn fieldless_enum_with_rename() {
+    let t = Role::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "type": "string",
+            "enum": ["SUPER_ADMIN", "guest", "EDITOR"],
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(vec![Role::SuperAdmin, Role::Anonymous, Role::Editor]).unwrap(),
+        json!(["SUPER_ADMIN", "guest", "EDITOR"])
+    );
+}
+
+#[derive(Swagger)]
+struct StructWithEnum {
+    status: Status,
+    role: Option<Role>,
+}
+
+#[test]
+fn struct_with_enum() {
+    let t = StructWithEnum::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "status": {
+                    "type": "string",
+                    "enum": ["Active", "Inactive"],
+                },
+                "role": {
+                    "type": "string",
+                    "enum": ["SUPER_ADMIN", "guest", "EDITOR"],
+                },
+            },
+            "required": ["status"],
+            "type": "object",
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+enum ExternallyTagged {
+    Unit,
+    Newtype(u8),
+    Tuple(u8, String),
+    Struct { val1: u8, val2: Option<String> },
+}
+
+#[test]
+fn externally_tagged_enum() {
+    let t = ExternallyTagged::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                {
+                    "type": "string",
+                    "enum": ["Unit"],
+                },
+                {
+                    "type": "object",
+                    "required": ["Newtype"],
+                    "properties": {
+                        "Newtype": {
+                            "maximum":255,
+                            "minimum":0,
+                            "type":"integer",
+                            "format":"int32",
+                        },
+                    },
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "required": ["Tuple"],
+                    "properties": {
+                        "Tuple": {
+                            "type": "array",
+                            "items": {
+                                "anyOf": [
+                                    {
+                                        "maximum":255,
+                                        "minimum":0,
+                                        "type":"integer",
+                                        "format":"int32",
+                                    },
+                                    {
+                                        "type": "string",
+                                    },
+                                ],
+                            },
+                            "minItems": 2,
+                            "maxItems": 2,
+                        },
+                    },
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "required": ["Struct"],
+                    "properties": {
+                        "Struct": {
+                            "type": "object",
+                            "required": ["val1"],
+                            "properties": {
+                                "val1": {
+                                    "maximum":255,
+                                    "minimum":0,
+                                    "type":"integer",
+                                    "format":"int32",
+                                },
+                                "val2": {
+                                    "type": "string",
+                                },
+                            },
+                        },
+                    },
+                    "additionalProperties": false,
+                },
+            ],
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(vec![
+            ExternallyTagged::Unit,
+            ExternallyTagged::Newtype(1),
+            ExternallyTagged::Tuple(1, "a".to_owned()),
+            ExternallyTagged::Struct {
+                val1: 1,
+                val2: None
+            },
+        ])
+        .unwrap(),
+        json!([
+            "Unit",
+            { "Newtype": 1 },
+            { "Tuple": [1, "a"] },
+            { "Struct": { "val1": 1, "val2": null } },
+        ])
+    );
+}
+
+#[derive(Serialize, Swagger)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum InternallyTagged {
+    Unit,
+    Newtype(MySubStruct),
+    Struct { val1: u8 },
+}
+
+#[test]
+fn internally_tagged_enum() {
+    let t = InternallyTagged::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": {
+                        "type": { "type": "string", "enum": ["unit"] },
+                    },
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["type"],
+                            "properties": {
+                                "type": { "type": "string", "enum": ["newtype"] },
+                            },
+                        },
+                        MySubStruct::get_json_schema_definition(),
+                    ],
+                },
+                {
+                    "type": "object",
+                    "required": ["type", "val1"],
+                    "properties": {
+                        "type": { "type": "string", "enum": ["struct"] },
+                        "val1": {
+                            "maximum":255,
+                            "minimum":0,
+                            "type":"integer",
+                            "format":"int32",
+                        },
+                    },
+                },
+            ],
+            "discriminator": {
+                "propertyName": "type",
+            },
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(vec![
+            InternallyTagged::Unit,
+            InternallyTagged::Newtype(MySubStruct {
+                val1: 1,
+                val2: "a".to_owned()
+            }),
+            InternallyTagged::Struct { val1: 1 },
+        ])
+        .unwrap(),
+        json!([
+            { "type": "unit" },
+            { "type": "newtype", "val1": 1, "val2": "a" },
+            { "type": "struct", "val1": 1 },
+        ])
+    );
+}
+
+#[derive(Serialize, Swagger)]
+#[serde(tag = "t", content = "c")]
+enum AdjacentlyTagged {
+    Unit,
+    Newtype(String),
+    Struct { val1: u8 },
+}
+
+#[test]
+fn adjacently_tagged_enum() {
+    let t = AdjacentlyTagged::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "required": ["t"],
+                    "properties": {
+                        "t": { "type": "string", "enum": ["Unit"] },
+                    },
+                },
+                {
+                    "type": "object",
+                    "required": ["t", "c"],
+                    "properties": {
+                        "t": { "type": "string", "enum": ["Newtype"] },
+                        "c": { "type": "string" },
+                    },
+                },
+                {
+                    "type": "object",
+                    "required": ["t", "c"],
+                    "properties": {
+                        "t": { "type": "string", "enum": ["Struct"] },
+                        "c": {
+                            "type": "object",
+                            "required": ["val1"],
+                            "properties": {
+                                "val1": {
+                                    "maximum":255,
+                                    "minimum":0,
+                                    "type":"integer",
+                                    "format":"int32",
+                                },
+                            },
+                        },
+                    },
+                },
+            ],
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(vec![
+            AdjacentlyTagged::Unit,
+            AdjacentlyTagged::Newtype("a".to_owned()),
+            AdjacentlyTagged::Struct { val1: 1 },
+        ])
+        .unwrap(),
+        json!([
+            { "t": "Unit" },
+            { "t": "Newtype", "c": "a" },
+            { "t": "Struct", "c": { "val1": 1 } },
+        ])
+    );
+}
+
+#[derive(Serialize, Swagger)]
+#[serde(untagged)]
+enum Untagged {
+    Unit,
+    Newtype(String),
+    Struct { val1: u8 },
+}
+
+#[test]
+fn untagged_enum() {
+    let t = Untagged::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "anyOf": [
+                {
+                    "nullable": true,
+                    "enum": [null],
+                },
+                {
+                    "type": "string",
+                },
+                {
+                    "type": "object",
+                    "required": ["val1"],
+                    "properties": {
+                        "val1": {
+                            "maximum":255,
+                            "minimum":0,
+                            "type":"integer",
+                            "format":"int32",
+                        },
+                    },
+                },
+            ],
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(vec![
+            Untagged::Unit,
+            Untagged::Newtype("a".to_owned()),
+            Untagged::Struct { val1: 1 },
+        ])
+        .unwrap(),
+        json!([null, "a", { "val1": 1 }])
+    );
+}
+
+#[derive(Serialize, Swagger)]
+struct UserId(u64);
+
+#[derive(Serialize, Swagger)]
+struct Point(i8, i8, String);
+
+#[derive(Serialize, Swagger)]
+struct Marker;
+
+#[test]
+fn newtype_struct() {
+    let t = UserId::get_json_schema_definition();
+
+    assert_eq!(t, u64::get_json_schema_definition());
+    assert_eq!(serde_json::to_value(UserId(1)).unwrap(), json!(1));
+}
+
+#[test]
+fn tuple_struct() {
+    let t = Point::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "type": "array",
+            // Each position accepts either type, OpenAPI 3.0 cannot tell them apart
+            "items": {
+                "anyOf": [
+                    {
+                        "maximum":127,
+                        "minimum":-128,
+                        "type":"integer",
+                        "format":"int32",
+                    },
+                    {
+                        "type": "string",
+                    },
+                ],
+            },
+            "minItems": 3,
+            "maxItems": 3,
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(Point(1, 2, "a".to_owned())).unwrap(),
+        json!([1, 2, "a"])
+    );
+}
+
+#[test]
+fn unit_struct() {
+    let t = Marker::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "nullable": true,
+            "enum": [null],
+        })
+    );
+    assert_eq!(serde_json::to_value(Marker).unwrap(), json!(null));
+}
+
+#[derive(Swagger)]
+struct StructWithNewtype {
+    id: UserId,
+    parent: Option<UserId>,
+}
+
+#[test]
+fn struct_with_newtype() {
+    let t = StructWithNewtype::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "id": u64::get_json_schema_definition(),
+                "parent": u64::get_json_schema_definition(),
+            },
+            "required": ["id"],
+            "type": "object",
+        })
+    );
+}
+
+#[derive(Swagger)]
+struct Page<T> {
+    items: Vec<T>,
+    next: Option<String>,
+}
+
+#[test]
+fn generic_struct() {
+    let t = Page::<MySubStruct>::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "items": {
+                    "type": "array",
+                    "items": MySubStruct::get_json_schema_definition(),
+                },
+                "next": {
+                    "type": "string",
+                },
+            },
+            "required": ["items"],
+            "type": "object",
+        })
+    );
+}
+
+#[derive(Swagger)]
+struct View<'a> {
+    name: &'a str,
+}
+
+#[test]
+fn struct_with_lifetime() {
+    let t = View::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "name": {
+                    "type": "string",
+                },
+            },
+            "required": ["name"],
+            "type": "object",
+        })
+    );
+}
+
+#[derive(Swagger)]
+enum ApiResponse<'a, T, E>
+where
+    T: Clone,
+{
+    Ok(T),
+    Err { error: E, message: &'a str },
+}
+
+#[test]
+fn generic_enum_with_where_clause() {
+    let t = ApiResponse::<u8, String>::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "required": ["Ok"],
+                    "properties": {
+                        "Ok": u8::get_json_schema_definition(),
+                    },
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "required": ["Err"],
+                    "properties": {
+                        "Err": {
+                            "type": "object",
+                            "required": ["error", "message"],
+                            "properties": {
+                                "error": {
+                                    "type": "string",
+                                },
+                                "message": {
+                                    "type": "string",
+                                },
+                            },
+                        },
+                    },
+                    "additionalProperties": false,
+                },
+            ],
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+#[serde(rename_all = "camelCase")]
+struct RenamedStruct {
+    first_name: String,
+    #[serde(rename = "surname")]
+    last_name: Option<String>,
+    r#type: u8,
+    #[serde(rename(serialize = "createdAt", deserialize = "created"))]
+    created_at: String,
+}
+
+#[test]
+fn struct_with_rename() {
+    let t = RenamedStruct::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "firstName": {
+                    "type": "string",
+                },
+                "surname": {
+                    "type": "string",
+                },
+                "type": {
+                    "maximum":255,
+                    "minimum":0,
+                    "type":"integer",
+                    "format":"int32",
+                },
+                "createdAt": {
+                    "type": "string",
+                },
+            },
+            "required": ["firstName", "type", "createdAt"],
+            "type": "object",
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(RenamedStruct {
+            first_name: "a".to_owned(),
+            last_name: None,
+            r#type: 1,
+            created_at: "b".to_owned(),
+        })
+        .unwrap(),
+        json!({
+            "firstName": "a",
+            "surname": null,
+            "type": 1,
+            "createdAt": "b",
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+#[serde(rename_all(serialize = "SCREAMING-KEBAB-CASE", deserialize = "PascalCase"))]
+struct RenamedSerializeDeserialize {
+    very_tasty: u8,
+}
+
+#[test]
+fn struct_with_rename_all_serialize() {
+    let t = RenamedSerializeDeserialize::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "VERY-TASTY": u8::get_json_schema_definition(),
+            },
+            "required": ["VERY-TASTY"],
+            "type": "object",
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+#[serde(
+    tag = "kind",
+    rename_all = "kebab-case",
+    rename_all_fields = "camelCase"
+)]
+enum RenamedEnum {
+    CreatedItem {
+        item_id: u8,
+    },
+    #[serde(rename_all = "UPPERCASE")]
+    DeletedItem {
+        item_id: u8,
+    },
+}
+
+#[test]
+fn enum_with_rename() {
+    let t = RenamedEnum::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "required": ["kind", "itemId"],
+                    "properties": {
+                        "kind": { "type": "string", "enum": ["created-item"] },
+                        "itemId": u8::get_json_schema_definition(),
+                    },
+                },
+                {
+                    "type": "object",
+                    "required": ["kind", "ITEM_ID"],
+                    "properties": {
+                        "kind": { "type": "string", "enum": ["deleted-item"] },
+                        "ITEM_ID": u8::get_json_schema_definition(),
+                    },
+                },
+            ],
+            "discriminator": {
+                "propertyName": "kind",
+            },
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(vec![
+            RenamedEnum::CreatedItem { item_id: 1 },
+            RenamedEnum::DeletedItem { item_id: 2 },
+        ])
+        .unwrap(),
+        json!([
+            { "kind": "created-item", "itemId": 1 },
+            { "kind": "deleted-item", "ITEM_ID": 2 },
+        ])
+    );
+}
+
+#[derive(Serialize, Swagger)]
+struct Audit {
+    created_by: String,
+    updated_by: Option<String>,
+}
+
+#[derive(Serialize, Swagger)]
+struct StructWithFlatten {
+    val1: u8,
+    #[serde(flatten)]
+    audit: Audit,
+    #[serde(flatten)]
+    extra: Option<MySubStruct>,
+    #[serde(flatten)]
+    event: InternallyTagged,
+}
+
+#[test]
+fn struct_with_flatten() {
+    let t = StructWithFlatten::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "val1": u8::get_json_schema_definition(),
+                "created_by": String::get_json_schema_definition(),
+                "updated_by": String::get_json_schema_definition(),
+                "val2": String::get_json_schema_definition(),
+            },
+            "required": ["val1", "created_by"],
+            "type": "object",
+            "allOf": [
+                InternallyTagged::get_json_schema_definition(),
+            ],
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(StructWithFlatten {
+            val1: 1,
+            audit: Audit {
+                created_by: "a".to_owned(),
+                updated_by: None,
+            },
+            extra: None,
+            event: InternallyTagged::Unit,
+        })
+        .unwrap(),
+        json!({
+            "val1": 1,
+            "created_by": "a",
+            "updated_by": null,
+            "type": "unit",
+        })
+    );
+}
+
+fn is_zero(val: &u8) -> bool {
+    *val == 0
+}
+
+#[derive(Serialize, Swagger)]
+struct StructWithSkip {
+    #[serde(skip)]
+    cache: Vec<u8>,
+    #[serde(default)]
+    val1: u8,
+    #[serde(skip_serializing_if = "is_zero")]
+    val2: u8,
+    #[serde(skip_serializing)]
+    password: String,
+    #[serde(skip_deserializing)]
+    id: u8,
+}
+
+#[test]
+fn struct_with_skip() {
+    let t = StructWithSkip::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "val1": u8::get_json_schema_definition(),
+                "val2": u8::get_json_schema_definition(),
+                "password": {
+                    "type": "string",
+                    "writeOnly": true,
+                },
+                "id": {
+                    "type": "integer",
+                    "format": "int32",
+                    "minimum": 0,
+                    "maximum": 255,
+                    "readOnly": true,
+                },
+            },
+            "required": ["password", "id"],
+            "type": "object",
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+#[serde(default)]
+struct StructWithContainerDefault {
+    val1: u8,
+    val2: String,
+}
+
+#[test]
+fn struct_with_container_default() {
+    let t = StructWithContainerDefault::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "val1": u8::get_json_schema_definition(),
+                "val2": String::get_json_schema_definition(),
+            },
+            "type": "object",
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+enum EnumWithSkip {
+    Visible,
+    #[serde(skip)]
+    Hidden,
+    #[serde(skip_serializing)]
+    Internal,
+}
+
+#[test]
+fn enum_with_skip() {
+    let t = EnumWithSkip::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "type": "string",
+            "enum": ["Visible"],
+        })
+    );
+}
+
+type Maybe<T> = Option<T>;
+
+#[derive(Swagger)]
+struct StructWithNestedOption {
+    val1: Vec<Option<u8>>,
+    val2: std::option::Option<u8>,
+    val3: core::option::Option<u8>,
+    #[swagger(required = false)]
+    val4: Maybe<u8>,
+    #[swagger(required = true)]
+    val5: Option<u8>,
+}
+
+#[test]
+fn struct_with_nested_option() {
+    let t = StructWithNestedOption::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "val1": {
+                    "type": "array",
+                    "items": u8::get_json_schema_definition(),
+                },
+                "val2": u8::get_json_schema_definition(),
+                "val3": u8::get_json_schema_definition(),
+                "val4": u8::get_json_schema_definition(),
+                "val5": u8::get_json_schema_definition(),
+            },
+            "required": ["val1", "val5"],
+            "type": "object",
+        })
+    );
+}
+
+/// A documented struct.
+///
+/// With more details.
+#[derive(Swagger)]
+struct StructWithDoc {
+    /// The first value.
+    ///
+    /// Any byte.
+    val1: u8,
+    val2: u8,
+}
+
+#[test]
+fn struct_with_doc() {
+    let t = StructWithDoc::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "title": "A documented struct.",
+            "description": "A documented struct.\n\nWith more details.",
+            "properties": {
+                "val1": {
+                    "type": "integer",
+                    "format": "int32",
+                    "minimum": u8::MIN,
+                    "maximum": u8::MAX,
+                    "title": "The first value.",
+                    "description": "The first value.\n\nAny byte.",
+                },
+                "val2": u8::get_json_schema_definition(),
+            },
+            "required": ["val1", "val2"],
+            "type": "object",
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+#[serde(tag = "type")]
+enum EnumWithDoc {
+    /// Nothing to report.
+    Empty,
+    Value {
+        val1: u8,
+    },
+}
+
+#[test]
+fn enum_with_doc() {
+    let t = EnumWithDoc::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                {
+                    "title": "Nothing to report.",
+                    "description": "Nothing to report.",
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": {
+                        "type": { "type": "string", "enum": ["Empty"] },
+                    },
+                },
+                {
+                    "type": "object",
+                    "required": ["type", "val1"],
+                    "properties": {
+                        "type": { "type": "string", "enum": ["Value"] },
+                        "val1": u8::get_json_schema_definition(),
+                    },
+                },
+            ],
+            "discriminator": { "propertyName": "type" },
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+struct StructWithSwaggerAttributes {
+    #[swagger(
+        description = "The user name",
+        example = "john",
+        min_length = 3,
+        max_length = 16
+    )]
+    #[swagger(pattern = "^[a-z]+$")]
+    name: String,
+    #[swagger(minimum = "-10", maximum = 10.5, format = "float")]
+    score: f32,
+    #[swagger(min_items = 1, max_items = 3, unique_items)]
+    tags: Vec<String>,
+    #[swagger(deprecated, read_only, nullable)]
+    legacy: Option<u8>,
+    #[serde(skip_serializing)]
+    #[swagger(write_only = false)]
+    password: String,
+}
+
+#[test]
+fn struct_with_swagger_attributes() {
+    let t = StructWithSwaggerAttributes::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "The user name",
+                    "example": "john",
+                    "minLength": 3,
+                    "maxLength": 16,
+                    "pattern": "^[a-z]+$",
+                },
+                "score": {
+                    "type": "number",
+                    "format": "float",
+                    "minimum": -10,
+                    "maximum": 10.5,
+                },
+                "tags": {
+                    "type": "array",
+                    "items": String::get_json_schema_definition(),
+                    "minItems": 1,
+                    "maxItems": 3,
+                    "uniqueItems": true,
+                },
+                "legacy": {
+                    "type": "integer",
+                    "format": "int32",
+                    "minimum": u8::MIN,
+                    "maximum": u8::MAX,
+                    "deprecated": true,
+                    "readOnly": true,
+                    "nullable": true,
+                },
+                "password": String::get_json_schema_definition(),
+            },
+            "required": ["name", "score", "tags", "password"],
+            "type": "object",
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+#[serde(rename = "Renamed")]
+struct StructWithContainerRename {
+    val1: u8,
+}
+
+#[test]
+fn schema_names() {
+    assert_eq!(MySubStruct::schema_name(), Some("MySubStruct".to_owned()));
+    assert_eq!(
+        StructWithContainerRename::schema_name(),
+        Some("Renamed".to_owned())
+    );
+    assert_eq!(
+        <Page<MySubStruct>>::schema_name(),
+        Some("Page_MySubStruct".to_owned())
+    );
+    // A parameter without a schema name goes by its type name
+    assert_eq!(<Page<u8>>::schema_name(), Some("Page_u8".to_owned()));
+    assert_eq!(
+        <Page<Vec<MySubStruct>>>::schema_name(),
+        Some("Page_Vec_MySubStruct".to_owned())
+    );
+    assert_eq!(<Vec<MySubStruct>>::schema_name(), None);
+}
+
+#[derive(Swagger)]
+struct TreeNode {
+    name: String,
+    children: Vec<TreeNode>,
+}
+
+#[derive(Swagger)]
+struct Comment {
+    text: String,
+    reply: Option<Box<Comment>>,
+}
+
+#[derive(Swagger)]
+struct Tree<T> {
+    value: T,
+    children: Vec<Tree<T>>,
+}
+
+#[test]
+fn recursive_struct() {
+    let tree_node = json!({
+        "properties": {
+            "name": String::get_json_schema_definition(),
+            "children": {
+                "type": "array",
+                "items": { "$ref": "#/components/schemas/TreeNode" },
+            },
+        },
+        "required": ["name", "children"],
+        "type": "object",
+    });
+    assert_eq!(TreeNode::get_json_schema_definition(), tree_node);
+    // The `$ref` points at the component schema left in the generator
+    let mut generator = SchemaGenerator::default();
+    assert_eq!(generator.root_schema_for::<TreeNode>(), tree_node);
+    assert_eq!(generator.take_definitions()["TreeNode"], tree_node);
+
+    let comment = json!({
+        "properties": {
+            "text": String::get_json_schema_definition(),
+            "reply": { "$ref": "#/components/schemas/Comment" },
+        },
+        "required": ["text"],
+        "type": "object",
+    });
+    assert_eq!(Comment::get_json_schema_definition(), comment);
+}
+
+#[test]
+fn recursive_generic_struct() {
+    assert_eq!(<Tree<u8>>::schema_name(), Some("Tree_u8".to_owned()));
+    let tree = json!({
+        "properties": {
+            "value": u8::get_json_schema_definition(),
+            "children": {
+                "type": "array",
+                "items": { "$ref": "#/components/schemas/Tree_u8" },
+            },
+        },
+        "required": ["value", "children"],
+        "type": "object",
+    });
+    assert_eq!(<Tree<u8>>::get_json_schema_definition(), tree);
+    let mut generator = SchemaGenerator::default();
+    assert_eq!(generator.root_schema_for::<Tree<u8>>(), tree);
+    assert_eq!(generator.take_definitions()["Tree_u8"], tree);
+}
+
+#[derive(Serialize, Swagger)]
+enum EnumWithFlatten {
+    Page {
+        #[serde(flatten)]
+        inner: MySubStruct,
+        total: u32,
+    },
+}
+
+#[test]
+fn enum_with_flatten() {
+    let t = EnumWithFlatten::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "required": ["Page"],
+                    "properties": {
+                        "Page": {
+                            "type": "object",
+                            "required": ["total", "val1", "val2"],
+                            "properties": {
+                                "total": u32::get_json_schema_definition(),
+                                "val1": u8::get_json_schema_definition(),
+                                "val2": String::get_json_schema_definition(),
+                            },
+                        },
+                    },
+                    "additionalProperties": false,
+                },
+            ],
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+struct StructWithMaps {
+    permissions: HashMap<Role, bool>,
+    statuses: BTreeMap<String, Vec<Status>>,
+    counters: BTreeMap<u16, u64>,
+}
+
+#[test]
+fn struct_with_maps() {
+    let t = StructWithMaps::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "type": "object",
+            "required": ["permissions", "statuses", "counters"],
+            "properties": {
+                "permissions": {
+                    "type": "object",
+                    "additionalProperties": { "type": "boolean" },
+                    "propertyNames": { "enum": ["SUPER_ADMIN", "guest", "EDITOR"] },
+                },
+                "statuses": {
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "array",
+                        "items": { "type": "string", "enum": ["Active", "Inactive"] },
+                    },
+                },
+                "counters": {
+                    "type": "object",
+                    "additionalProperties": { "type": "integer", "minimum": 0 },
+                },
+            },
+        })
+    );
+
+    let maps = StructWithMaps {
+        permissions: vec![(Role::Anonymous, false)].into_iter().collect(),
+        statuses: vec![("current".to_owned(), vec![Status::Active])]
+            .into_iter()
+            .collect(),
+        counters: vec![(7, 1)].into_iter().collect(),
+    };
+    assert_eq!(
+        serde_json::to_value(maps).unwrap(),
+        json!({
+            "permissions": { "guest": false },
+            "statuses": { "current": ["Active"] },
+            "counters": { "7": 1 },
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+struct StructWithCollections {
+    tags: BTreeSet<String>,
+    point: (i8, i8, String),
+    rgb: [u8; 3],
+}
+
+#[test]
+fn struct_with_collections() {
+    let t = StructWithCollections::get_json_schema_definition();
+
+    let i8_schema =
+        json!({ "type": "integer", "format": "int32", "minimum": -128, "maximum": 127 });
+    assert_eq!(
+        t,
+        json!({
+            "type": "object",
+            "required": ["tags", "point", "rgb"],
+            "properties": {
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "uniqueItems": true,
+                },
+                "point": {
+                    "type": "array",
+                    "items": { "anyOf": [i8_schema, { "type": "string" }] },
+                    "minItems": 3,
+                    "maxItems": 3,
+                },
+                "rgb": {
+                    "type": "array",
+                    "items": { "type": "integer", "format": "int32", "minimum": 0, "maximum": 255 },
+                    "minItems": 3,
+                    "maxItems": 3,
+                },
+            },
+        })
+    );
+
+    let collections = StructWithCollections {
+        tags: vec!["a".to_owned()].into_iter().collect(),
+        point: (1, -1, "origin".to_owned()),
+        rgb: [0, 128, 255],
+    };
+    assert_eq!(
+        serde_json::to_value(collections).unwrap(),
+        json!({
+            "tags": ["a"],
+            "point": [1, -1, "origin"],
+            "rgb": [0, 128, 255],
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+struct Config {
+    name: String,
+}
+
+#[derive(Serialize, Swagger)]
+struct StructWithPointers<'a> {
+    config: Arc<Config>,
+    label: Cow<'a, str>,
+    history: Rc<RefCell<Vec<u8>>>,
+    parent: Option<&'a Config>,
+}
+
+#[test]
+fn struct_with_pointers() {
+    let t = StructWithPointers::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "type": "object",
+            "required": ["config", "label", "history"],
+            "properties": {
+                "config": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "name": { "type": "string" } },
+                },
+                "label": { "type": "string" },
+                "history": {
+                    "type": "array",
+                    "items": { "type": "integer", "format": "int32", "minimum": 0, "maximum": 255 },
+                },
+                "parent": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "name": { "type": "string" } },
+                },
+            },
+        })
+    );
+
+    let parent = Config {
+        name: "parent".to_owned(),
+    };
+    let pointers = StructWithPointers {
+        config: Arc::new(Config {
+            name: "child".to_owned(),
+        }),
+        label: Cow::Borrowed("label"),
+        history: Rc::new(RefCell::new(vec![1])),
+        parent: Some(&parent),
+    };
+    assert_eq!(
+        serde_json::to_value(pointers).unwrap(),
+        json!({
+            "config": { "name": "child" },
+            "label": "label",
+            "history": [1],
+            "parent": { "name": "parent" },
+        })
+    );
+}
+
+#[derive(Swagger)]
+struct StructWithIntegersAsStrings {
+    #[swagger(as_string)]
+    id: u64,
+    #[swagger(as_string)]
+    parents: Vec<i32>,
+    #[swagger(as_string = false)]
+    size: u64,
+}
+
+#[test]
+fn struct_with_integers_as_strings() {
+    let t = StructWithIntegersAsStrings::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "type": "object",
+            "required": ["id", "parents", "size"],
+            "properties": {
+                "id": { "type": "string", "pattern": "^[0-9]+$" },
+                "parents": {
+                    "type": "array",
+                    "items": { "type": "string", "format": "int32", "pattern": "^-?[0-9]+$" },
+                },
+                "size": { "type": "integer", "minimum": 0 },
+            },
+        })
+    );
+}
+
+/// Serialized by hand, without any schema.
+struct Opaque;
+
+impl serde::Serialize for Opaque {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(1)
+    }
+}
+
+#[derive(Serialize, Swagger)]
+struct StructWithArbitraryJson {
+    metadata: serde_json::Value,
+    labels: serde_json::Map<String, serde_json::Value>,
+    #[swagger(any)]
+    opaque: Opaque,
+    #[swagger(any, description = "Anything")]
+    extra: Option<Opaque>,
+}
+
+#[test]
+fn struct_with_arbitrary_json() {
+    let t = StructWithArbitraryJson::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "type": "object",
+            "required": ["metadata", "labels", "opaque"],
+            "properties": {
+                "metadata": {},
+                "labels": { "type": "object", "additionalProperties": true },
+                "opaque": {},
+                "extra": { "description": "Anything" },
+            },
+        })
+    );
+
+    let value = StructWithArbitraryJson {
+        metadata: json!([1, "two"]),
+        labels: serde_json::Map::new(),
+        opaque: Opaque,
+        extra: None,
+    };
+    assert_eq!(
+        serde_json::to_value(value).unwrap(),
+        json!({ "metadata": [1, "two"], "labels": {}, "opaque": 1, "extra": null })
+    );
+}
@@ -1,10 +1,10 @@
 #![allow(dead_code)]
 
-use std::collections::HashMap;
-
 extern crate proc_macro;
 extern crate proc_macro2;
 #[macro_use]
+extern crate serde_derive;
+#[macro_use]
 extern crate serde_json;
 
 #[macro_use]
@@ -12,8 +12,8 @@ extern crate struct2swagger;
 #[macro_use]
 extern crate struct2swagger_derive;
 
-use struct2swagger::swagger_object::SwaggerObject;
-use struct2swagger::{JsonSchemaDefinition, QueryDefinition};
+use struct2swagger::swagger_object::{SwaggerObject, SwaggerVersion};
+use struct2swagger::{JsonSchemaDefinition, QueryDefinition, SchemaSettings};
 
 #[derive(Swagger)]
 struct SimpleStruct {
@@ -321,3 +321,723 @@ fn many_methods() {
         })
     );
 }
+
+#[derive(Deserialize, Swagger)]
+enum Status {
+    Active,
+    Inactive,
+}
+
+#[derive(Deserialize, Swagger)]
+struct StatusFilter {
+    status: Option<Status>,
+}
+
+#[test]
+fn with_enum_query_string() {
+    // An enum only makes a query parameter as the field of a struct
+    assert!(Status::get_query_definitions().is_empty());
+
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION);
+
+    swagger_add_router!(
+        swagger_object,
+        "GET",
+        "/",
+        StatusFilter,
+        200,
+        DESCRIPTION,
+        SimpleStruct
+    );
+
+    let stringified = serde_json::to_string(&swagger_object).unwrap();
+    let values: serde_json::Value = serde_json::from_str(&stringified).unwrap();
+
+    assert_eq!(
+        values["paths"]["/"]["get"]["parameters"],
+        json!([
+            {
+                "name": "status",
+                "in": "query",
+                "required": false,
+                "schema": {
+                    "type": "string",
+                    "enum": ["Active", "Inactive"],
+                },
+            },
+        ])
+    );
+}
+
+#[derive(Swagger)]
+struct SimpleStructQuery(SimpleStruct);
+
+#[derive(Swagger)]
+struct OptionalStructQuery(StatusFilter);
+
+#[derive(Swagger)]
+struct Id(u64);
+
+#[test]
+fn with_newtype_query_string() {
+    let parameters = serde_json::to_value(SimpleStructQuery::get_query_definitions()).unwrap();
+    assert_eq!(
+        parameters,
+        serde_json::to_value(SimpleStruct::get_query_definitions()).unwrap()
+    );
+
+    let parameters = serde_json::to_value(OptionalStructQuery::get_query_definitions()).unwrap();
+    assert_eq!(
+        parameters,
+        serde_json::to_value(StatusFilter::get_query_definitions()).unwrap()
+    );
+
+    assert!(Id::get_query_definitions().is_empty());
+}
+
+#[derive(Deserialize, Swagger)]
+#[serde(rename_all = "camelCase")]
+struct RenamedQuery {
+    page_size: Option<u8>,
+    #[serde(rename(serialize = "sortBy", deserialize = "sort"))]
+    sort_by: String,
+}
+
+#[test]
+fn with_renamed_query_string() {
+    let parameters = serde_json::to_value(RenamedQuery::get_query_definitions()).unwrap();
+
+    assert_eq!(
+        parameters,
+        json!([
+            {
+                "name": "pageSize",
+                "in": "query",
+                "required": false,
+                "schema": <u8>::get_json_schema_definition(),
+            },
+            {
+                "name": "sort",
+                "in": "query",
+                "required": true,
+                "schema": String::get_json_schema_definition(),
+            },
+        ])
+    );
+}
+
+#[derive(Deserialize, Swagger)]
+struct Pagination {
+    page: u32,
+    per_page: Option<u8>,
+}
+
+#[derive(Deserialize, Swagger)]
+struct SearchQuery {
+    q: String,
+    #[serde(flatten)]
+    pagination: Pagination,
+    #[serde(flatten)]
+    filter: Option<StatusFilter>,
+}
+
+#[test]
+fn with_flattened_query_string() {
+    let parameters = serde_json::to_value(SearchQuery::get_query_definitions()).unwrap();
+
+    assert_eq!(
+        parameters,
+        json!([
+            {
+                "name": "q",
+                "in": "query",
+                "required": true,
+                "schema": String::get_json_schema_definition(),
+            },
+            {
+                "name": "page",
+                "in": "query",
+                "required": true,
+                "schema": <u32>::get_json_schema_definition(),
+            },
+            {
+                "name": "per_page",
+                "in": "query",
+                "required": false,
+                "schema": <u8>::get_json_schema_definition(),
+            },
+            {
+                "name": "status",
+                "in": "query",
+                "required": false,
+                "schema": Status::get_json_schema_definition(),
+            },
+        ])
+    );
+}
+
+#[derive(Serialize, Deserialize, Swagger)]
+struct Sorting {
+    #[serde(rename(serialize = "sortBy", deserialize = "sort"))]
+    sort_by: String,
+    #[serde(skip_deserializing)]
+    rank: u8,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    direction: String,
+}
+
+#[derive(Deserialize, Swagger)]
+struct SortedQuery {
+    #[serde(flatten)]
+    sorting: Sorting,
+}
+
+#[derive(Deserialize, Swagger)]
+struct SortedQueryNewtype(Sorting);
+
+#[test]
+fn with_flattened_query_string_like_deserialize() {
+    let expected = json!([
+        {
+            "name": "sort",
+            "in": "query",
+            "required": true,
+            "schema": String::get_json_schema_definition(),
+        },
+        {
+            "name": "direction",
+            "in": "query",
+            "required": true,
+            "schema": String::get_json_schema_definition(),
+        },
+    ]);
+
+    assert_eq!(
+        serde_json::to_value(SortedQuery::get_query_definitions()).unwrap(),
+        expected
+    );
+    assert_eq!(
+        serde_json::to_value(SortedQueryNewtype::get_query_definitions()).unwrap(),
+        expected
+    );
+}
+
+#[derive(Deserialize, Swagger)]
+struct QueryWithSkip {
+    #[serde(default)]
+    page: u32,
+    #[serde(skip)]
+    cache: Vec<u8>,
+    #[serde(skip_deserializing)]
+    computed: u8,
+    #[serde(skip_serializing)]
+    token: String,
+}
+
+#[test]
+fn with_skipped_query_string() {
+    let parameters = serde_json::to_value(QueryWithSkip::get_query_definitions()).unwrap();
+
+    assert_eq!(
+        parameters,
+        json!([
+            {
+                "name": "page",
+                "in": "query",
+                "required": false,
+                "schema": <u32>::get_json_schema_definition(),
+            },
+            {
+                "name": "token",
+                "in": "query",
+                "required": true,
+                "schema": String::get_json_schema_definition(),
+            },
+        ])
+    );
+}
+
+#[derive(Swagger)]
+struct DocumentedQuery {
+    /// Words to look for.
+    q: String,
+    limit: Option<u8>,
+}
+
+#[test]
+fn with_documented_query_string() {
+    let parameters = serde_json::to_value(DocumentedQuery::get_query_definitions()).unwrap();
+
+    assert_eq!(
+        parameters,
+        json!([
+            {
+                "name": "q",
+                "in": "query",
+                "description": "Words to look for.",
+                "required": true,
+                "schema": String::get_json_schema_definition(),
+            },
+            {
+                "name": "limit",
+                "in": "query",
+                "required": false,
+                "schema": <Option<u8>>::get_json_schema_definition(),
+            },
+        ])
+    );
+}
+
+#[derive(Swagger)]
+struct QueryWithSwaggerAttributes {
+    #[swagger(description = "Page size", minimum = 1, maximum = 100)]
+    limit: u8,
+    #[swagger(deprecated)]
+    offset: Option<u32>,
+}
+
+#[test]
+fn with_swagger_attributes_query_string() {
+    let parameters =
+        serde_json::to_value(QueryWithSwaggerAttributes::get_query_definitions()).unwrap();
+
+    assert_eq!(
+        parameters,
+        json!([
+            {
+                "name": "limit",
+                "in": "query",
+                "description": "Page size",
+                "required": true,
+                "schema": { "type": "integer", "format": "int32", "minimum": 1, "maximum": 100 },
+            },
+            {
+                "name": "offset",
+                "in": "query",
+                "required": false,
+                "deprecated": true,
+                "schema": <Option<u32>>::get_json_schema_definition(),
+            },
+        ])
+    );
+}
+
+#[derive(Swagger)]
+struct Author {
+    name: String,
+}
+
+#[derive(Swagger)]
+struct Book {
+    title: String,
+    author: Author,
+    coauthors: Vec<Author>,
+}
+
+#[derive(Swagger)]
+struct BookFilter {
+    author: Option<Author>,
+}
+
+#[test]
+fn with_component_schemas() {
+    let mut swagger_object = SwaggerObject::with_settings(
+        TITLE,
+        VERSION,
+        SchemaSettings {
+            component_schemas: true,
+            ..SchemaSettings::default()
+        },
+    );
+
+    swagger_add_router!(
+        swagger_object,
+        "GET",
+        "/books",
+        BookFilter,
+        200,
+        DESCRIPTION,
+        Book
+    );
+    swagger_add_router!(swagger_object, "GET", "/author", 200, DESCRIPTION, Author);
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let author_ref = json!({ "$ref": "#/components/schemas/Author" });
+
+    assert_eq!(
+        values["paths"]["/books"]["get"]["parameters"],
+        json!([
+            {
+                "name": "author",
+                "in": "query",
+                "required": false,
+                "schema": author_ref,
+            },
+        ])
+    );
+    assert_eq!(
+        values["paths"]["/books"]["get"]["responses"]["200"]["content"]["application/json"]
+            ["schema"],
+        json!({ "$ref": "#/components/schemas/Book" })
+    );
+    assert_eq!(
+        values["paths"]["/author"]["get"]["responses"]["200"]["content"]["application/json"]
+            ["schema"],
+        author_ref
+    );
+    assert_eq!(
+        values["components"],
+        json!({
+            "schemas": {
+                "Author": Author::get_json_schema_definition(),
+                "Book": {
+                    "type": "object",
+                    "required": ["title", "author", "coauthors"],
+                    "properties": {
+                        "title": String::get_json_schema_definition(),
+                        "author": author_ref,
+                        "coauthors": { "type": "array", "items": author_ref },
+                    },
+                },
+            },
+        })
+    );
+}
+
+#[derive(Swagger)]
+#[serde(tag = "type")]
+enum Shape {
+    Circle { radius: f64 },
+    Square { side: f64 },
+}
+
+#[test]
+fn with_internally_tagged_enum_components() {
+    let mut swagger_object = SwaggerObject::with_settings(
+        TITLE,
+        VERSION,
+        SchemaSettings {
+            component_schemas: true,
+            ..SchemaSettings::default()
+        },
+    );
+
+    assert_eq!(
+        swagger_object.schema_for::<Shape>(),
+        json!({ "$ref": "#/components/schemas/Shape" })
+    );
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let circle_ref = "#/components/schemas/Shape_Circle";
+    let square_ref = "#/components/schemas/Shape_Square";
+    assert_eq!(
+        values["components"]["schemas"]["Shape"],
+        json!({
+            "oneOf": [{ "$ref": circle_ref }, { "$ref": square_ref }],
+            "discriminator": {
+                "propertyName": "type",
+                "mapping": { "Circle": circle_ref, "Square": square_ref },
+            },
+        })
+    );
+    assert_eq!(
+        values["components"]["schemas"]["Shape_Circle"],
+        json!({
+            "type": "object",
+            "required": ["type", "radius"],
+            "properties": {
+                "type": { "type": "string", "enum": ["Circle"] },
+                "radius": f64::get_json_schema_definition(),
+            },
+        })
+    );
+    assert!(values["components"]["schemas"]["Shape_Square"].is_object());
+}
+
+#[test]
+fn with_flattened_option() {
+    let mut swagger_object = SwaggerObject::with_settings(
+        TITLE,
+        VERSION,
+        SchemaSettings {
+            component_schemas: true,
+            ..SchemaSettings::default()
+        },
+    );
+    let status_ref = json!({ "$ref": "#/components/schemas/Status" });
+
+    assert_eq!(
+        swagger_object.schema_for::<SearchQuery>(),
+        json!({ "$ref": "#/components/schemas/SearchQuery" })
+    );
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    assert_eq!(
+        values["components"]["schemas"]["SearchQuery"],
+        json!({
+            "type": "object",
+            "required": ["q", "page"],
+            "properties": {
+                "q": String::get_json_schema_definition(),
+                "page": <u32>::get_json_schema_definition(),
+                "per_page": <u8>::get_json_schema_definition(),
+                "status": status_ref,
+            },
+        })
+    );
+    let parameters: Vec<String> = swagger_object
+        .query_parameters_for::<SearchQuery>()
+        .into_iter()
+        .map(|parameter| parameter.name)
+        .collect();
+    assert_eq!(parameters, ["q", "page", "per_page", "status"]);
+
+    let mut swagger_object = SwaggerObject::with_settings(
+        TITLE,
+        VERSION,
+        SchemaSettings {
+            option_nullable: true,
+            ..SchemaSettings::default()
+        },
+    );
+
+    assert_eq!(
+        swagger_object.schema_for::<SearchQuery>(),
+        json!({
+            "type": "object",
+            "required": ["q", "page"],
+            "properties": {
+                "q": String::get_json_schema_definition(),
+                "page": <u32>::get_json_schema_definition(),
+                "per_page": {
+                    "type": "integer",
+                    "format": "int32",
+                    "minimum": 0,
+                    "maximum": 255,
+                    "nullable": true,
+                },
+                "status": {
+                    "type": "string",
+                    "enum": ["Active", "Inactive"],
+                    "nullable": true,
+                },
+            },
+        })
+    );
+}
+
+#[derive(Swagger)]
+struct Category {
+    name: String,
+    author: Author,
+    subcategories: Vec<Category>,
+}
+
+#[test]
+fn with_recursive_response() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION);
+
+    swagger_add_router!(
+        swagger_object,
+        "GET",
+        "/categories",
+        200,
+        DESCRIPTION,
+        Category
+    );
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let category_ref = json!({ "$ref": "#/components/schemas/Category" });
+
+    assert_eq!(
+        values["paths"]["/categories"]["get"]["responses"]["200"]["content"]["application/json"]
+            ["schema"],
+        category_ref
+    );
+    // Only the recursive type needs a component, the others stay inlined
+    assert_eq!(
+        values["components"],
+        json!({
+            "schemas": {
+                "Category": {
+                    "type": "object",
+                    "required": ["name", "author", "subcategories"],
+                    "properties": {
+                        "name": String::get_json_schema_definition(),
+                        "author": Author::get_json_schema_definition(),
+                        "subcategories": { "type": "array", "items": category_ref },
+                    },
+                },
+            },
+        })
+    );
+}
+
+/// Implemented the way it was before `json_schema` existed.
+struct LegacyId;
+
+impl JsonSchemaDefinition for LegacyId {
+    fn get_json_schema_definition() -> serde_json::Value {
+        json!({ "type": "string", "format": "legacy" })
+    }
+}
+
+#[derive(Swagger)]
+struct Profile {
+    id: LegacyId,
+    nickname: Option<String>,
+    author: Option<Author>,
+}
+
+#[test]
+fn with_openapi_3_1_settings() {
+    let mut swagger_object = SwaggerObject::with_settings(
+        TITLE,
+        VERSION,
+        SchemaSettings {
+            version: SwaggerVersion::V310,
+            component_schemas: true,
+            option_nullable: true,
+            ..SchemaSettings::default()
+        },
+    );
+
+    swagger_add_router!(swagger_object, "GET", "/profile", 200, DESCRIPTION, Profile);
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(values["openapi"], "3.1.0");
+    assert_eq!(
+        values["components"]["schemas"]["Profile"],
+        json!({
+            "type": "object",
+            "required": ["id"],
+            "properties": {
+                "id": { "type": "string", "format": "legacy" },
+                "nickname": { "type": ["string", "null"] },
+                "author": {
+                    "anyOf": [
+                        { "$ref": "#/components/schemas/Author" },
+                        { "type": "null" },
+                    ],
+                },
+            },
+        })
+    );
+}
+
+#[derive(Deserialize, Swagger)]
+struct TransferFilter {
+    account: u64,
+    #[swagger(as_string = false)]
+    limit: u64,
+}
+
+#[derive(Serialize, Swagger)]
+struct Transfer {
+    id: i64,
+    amount: u32,
+}
+
+#[test]
+fn with_integers_as_strings() {
+    let mut swagger_object = SwaggerObject::with_settings(
+        TITLE,
+        VERSION,
+        SchemaSettings {
+            integers_as_strings: true,
+            ..SchemaSettings::default()
+        },
+    );
+
+    swagger_add_router!(
+        swagger_object,
+        "GET",
+        "/transfers",
+        TransferFilter,
+        200,
+        DESCRIPTION,
+        Transfer
+    );
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let get = &values["paths"]["/transfers"]["get"];
+
+    assert_eq!(
+        get["parameters"],
+        json!([
+            {
+                "in": "query",
+                "name": "account",
+                "required": true,
+                "schema": { "type": "string", "pattern": "^[0-9]+$" },
+            },
+            {
+                "in": "query",
+                "name": "limit",
+                "required": true,
+                "schema": { "type": "integer", "minimum": 0 },
+            },
+        ])
+    );
+    assert_eq!(
+        get["responses"]["200"]["content"]["application/json"]["schema"],
+        json!({
+            "type": "object",
+            "required": ["id", "amount"],
+            "properties": {
+                "id": { "type": "string", "format": "int64", "pattern": "^-?[0-9]+$" },
+                "amount": {
+                    "type": "integer",
+                    "format": "int64",
+                    "minimum": 0,
+                    "maximum": 4_294_967_295u32,
+                },
+            },
+        })
+    );
+}
+
+#[derive(Serialize, Swagger)]
+struct ExportedTransfers {
+    #[swagger(as_string)]
+    exported: Transfer,
+    transfer: Transfer,
+}
+
+#[derive(Serialize, Swagger)]
+struct TransfersExported {
+    transfer: Transfer,
+    #[swagger(as_string)]
+    exported: Transfer,
+}
+
+#[test]
+fn with_integers_as_strings_components() {
+    let mut swagger_object = SwaggerObject::with_settings(
+        TITLE,
+        VERSION,
+        SchemaSettings {
+            component_schemas: true,
+            ..SchemaSettings::default()
+        },
+    );
+    let properties = json!({
+        "transfer": { "$ref": "#/components/schemas/Transfer" },
+        "exported": { "$ref": "#/components/schemas/Transfer_as_string" },
+    });
+
+    swagger_object.schema_for::<ExportedTransfers>();
+    swagger_object.schema_for::<TransfersExported>();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let schemas = &values["components"]["schemas"];
+    assert_eq!(schemas["ExportedTransfers"]["properties"], properties);
+    assert_eq!(schemas["TransfersExported"]["properties"], properties);
+    assert_eq!(schemas["Transfer"], Transfer::get_json_schema_definition());
+    assert_eq!(
+        schemas["Transfer_as_string"]["properties"],
+        json!({
+            "id": { "type": "string", "format": "int64", "pattern": "^-?[0-9]+$" },
+            "amount": { "type": "string", "format": "int64", "pattern": "^[0-9]+$" },
+        })
+    );
+}
@@ -0,0 +1,13 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+struct Opaque;
+
+#[derive(Swagger)]
+struct Page {
+    #[serde(flatten)]
+    #[swagger(any)]
+    inner: Opaque,
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+#[serde(untagged, tag = "type")]
+enum Shape {
+    Circle { radius: u32 },
+}
+
+#[derive(Swagger)]
+#[serde(content = "value")]
+enum Value {
+    Int(i32),
+}
+
+#[derive(Swagger)]
+struct Page {
+    #[serde(flatten, skip_serializing)]
+    inner: Shape,
+}
+
+fn main() {}
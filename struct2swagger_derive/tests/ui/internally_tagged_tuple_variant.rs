@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+#[serde(tag = "type")]
+enum Shape {
+    Point(i32, i32),
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+struct Person {
+    #[swagger(description = 1, min_length = "3", pattern)]
+    name: String,
+    #[swagger(minimum = "low", maximum = true, required = "yes")]
+    age: u8,
+    #[swagger(example(1))]
+    score: f32,
+    #[swagger("tags")]
+    tags: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+struct Person {
+    #[swagger(read_only, write_only)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+union Number {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+#[swagger(title = "Person")]
+struct Person {
+    #[swagger(summary = "The name")]
+    name: String,
+}
+
+#[derive(Swagger)]
+enum Status {
+    #[swagger(hidden)]
+    Active,
+}
+
+fn main() {}